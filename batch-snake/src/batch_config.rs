@@ -0,0 +1,243 @@
+use super::snake::ai::battlesnake::{BattlesnakeSnakeController, Error as BattlesnakeError};
+use super::snake::ai::greedy::GreedySnakeController;
+use super::snake::ai::hamiltonian::HamiltonianSnakeController;
+use super::snake::ai::pathfinding::PathfindingSnakeController;
+use super::snake::ai::process::{Error as ProcessError, ProcessSnakeController};
+use super::snake::level::{Error as LevelError, Level};
+use super::snake::tournament::{Config as TournamentConfig, Entrant, Error as TournamentError};
+use super::snake::world::{
+    Config as WorldConfig, CreateError as WorldCreateError, SnakeController,
+};
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    Json(serde_json::Error),
+    Level(LevelError),
+    Create(WorldCreateError),
+    Process(ProcessError),
+    Battlesnake(BattlesnakeError),
+    Tournament(TournamentError),
+    EmptyCommand,
+    NoBots,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "io error: {}", err),
+            Self::Json(err) => write!(f, "json error: {}", err),
+            Self::Level(err) => write!(f, "level error: {:?}", err),
+            Self::Create(err) => write!(f, "world create error: {:?}", err),
+            Self::Process(err) => write!(f, "process bot error: {:?}", err),
+            Self::Battlesnake(err) => write!(f, "battlesnake bot error: {:?}", err),
+            Self::Tournament(err) => write!(f, "tournament error: {:?}", err),
+            Self::EmptyCommand => write!(f, "process bot command is empty"),
+            Self::NoBots => write!(f, "no bots configured"),
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(err: serde_json::Error) -> Self {
+        Self::Json(err)
+    }
+}
+
+impl From<LevelError> for Error {
+    fn from(err: LevelError) -> Self {
+        Self::Level(err)
+    }
+}
+
+impl From<WorldCreateError> for Error {
+    fn from(err: WorldCreateError) -> Self {
+        Self::Create(err)
+    }
+}
+
+impl From<ProcessError> for Error {
+    fn from(err: ProcessError) -> Self {
+        Self::Process(err)
+    }
+}
+
+impl From<TournamentError> for Error {
+    fn from(err: TournamentError) -> Self {
+        Self::Tournament(err)
+    }
+}
+
+impl From<BattlesnakeError> for Error {
+    fn from(err: BattlesnakeError) -> Self {
+        Self::Battlesnake(err)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BotConfig {
+    Greedy,
+    Pathfinding,
+    Hamiltonian,
+    Process {
+        command: Vec<String>,
+        #[serde(default = "default_bot_timeout_millis")]
+        timeout_millis: u64,
+    },
+    Battlesnake {
+        url: String,
+        #[serde(default = "default_bot_timeout_millis")]
+        timeout_millis: u64,
+    },
+}
+
+impl BotConfig {
+    pub fn get_name(&self) -> String {
+        match self {
+            Self::Greedy => "greedy".to_string(),
+            Self::Pathfinding => "pathfinding".to_string(),
+            Self::Hamiltonian => "hamiltonian".to_string(),
+            Self::Process { command, .. } => format!("process:{}", command.join(" ")),
+            Self::Battlesnake { url, .. } => format!("battlesnake:{}", url),
+        }
+    }
+    pub fn controller(
+        &self,
+        game_id: &str,
+        snake_number: usize,
+    ) -> Result<Arc<Mutex<dyn SnakeController>>, Error> {
+        let controller: Arc<Mutex<dyn SnakeController>> = match self {
+            Self::Greedy => Arc::new(Mutex::new(GreedySnakeController::new())),
+            Self::Pathfinding => Arc::new(Mutex::new(PathfindingSnakeController::new())),
+            Self::Hamiltonian => Arc::new(Mutex::new(HamiltonianSnakeController::new())),
+            Self::Process {
+                command,
+                timeout_millis,
+            } => {
+                let (program, args) = command.split_first().ok_or(Error::EmptyCommand)?;
+                let mut command = Command::new(program);
+                command.args(args);
+                let timeout = Duration::from_millis(*timeout_millis);
+                Arc::new(Mutex::new(ProcessSnakeController::spawn(
+                    command,
+                    snake_number,
+                    timeout,
+                )?))
+            }
+            Self::Battlesnake {
+                url,
+                timeout_millis,
+            } => {
+                let timeout = Duration::from_millis(*timeout_millis);
+                let controller = BattlesnakeSnakeController::new(url, game_id, timeout)?;
+                Arc::new(Mutex::new(controller))
+            }
+        };
+        Ok(controller)
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct BatchConfig {
+    pub world: WorldConfig,
+    #[serde(default)]
+    pub level: Option<String>,
+    pub bots: Vec<BotConfig>,
+    #[serde(default = "default_matches")]
+    pub matches: usize,
+    #[serde(default = "default_max_ticks")]
+    pub max_ticks: usize,
+    #[serde(default = "default_threads")]
+    pub threads: usize,
+    #[serde(default)]
+    pub tournament: Option<TournamentConfig>,
+    #[serde(skip)]
+    loaded_level: Option<Level>,
+}
+
+impl BatchConfig {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let mut batch_config: Self = serde_json::from_str(&fs::read_to_string(path)?)?;
+        if batch_config.bots.is_empty() {
+            return Err(Error::NoBots);
+        }
+        if let Some(level_path) = &batch_config.level {
+            batch_config.loaded_level = Some(Level::load(level_path)?);
+        }
+        Ok(batch_config)
+    }
+    pub fn world_config(&self, match_number: usize) -> Result<WorldConfig, Error> {
+        let mut world_config = self.base_world_config();
+        world_config.seed = world_config.seed.wrapping_add(match_number as u64);
+        let game_id = format!("batch-{}", match_number);
+        for (snake_number, bot) in self.bots.iter().enumerate() {
+            world_config
+                .snakes_controllers
+                .insert(snake_number, bot.controller(&game_id, snake_number)?);
+        }
+        Ok(world_config)
+    }
+    pub fn base_world_config(&self) -> WorldConfig {
+        match &self.loaded_level {
+            Some(level) => level.world_config(self.world.clone()),
+            None => self.world.clone(),
+        }
+    }
+    pub fn entrants(&self) -> Vec<Entrant> {
+        self.bots
+            .iter()
+            .enumerate()
+            .map(|(index, bot)| {
+                let name = bot.get_name();
+                let is_duplicate = self
+                    .bots
+                    .iter()
+                    .filter(|other| other.get_name() == name)
+                    .count()
+                    > 1;
+                let name = if is_duplicate {
+                    format!("{}#{}", name, index)
+                } else {
+                    name
+                };
+                let bot = bot.clone();
+                Entrant::new(&name, move |snake_number| {
+                    bot.controller("tournament", snake_number)
+                        .map_err(|err| err.to_string())
+                })
+            })
+            .collect()
+    }
+}
+
+pub fn default_bot_timeout_millis() -> u64 {
+    500
+}
+
+pub fn default_matches() -> usize {
+    1
+}
+
+pub fn default_max_ticks() -> usize {
+    10000
+}
+
+pub fn default_threads() -> usize {
+    1
+}
@@ -0,0 +1,270 @@
+extern crate snake;
+
+mod batch_config;
+
+use batch_config::{BatchConfig, Error};
+use snake::game::Game;
+use snake::tournament::Tournament;
+use snake::world::{MatchEnd, SnakeStats};
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::process::ExitCode;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
+
+const DEFAULT_TOURNAMENT_RESULTS_PATH: &str = "tournament_results.json";
+
+#[derive(Debug, Clone, Serialize)]
+struct SnakeRecord {
+    number: usize,
+    bot: String,
+    score: usize,
+    stats: SnakeStats,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct MatchRecord {
+    match_number: usize,
+    seed: u64,
+    ticks: usize,
+    end: Option<MatchEnd>,
+    winner: Option<usize>,
+    winners: Vec<usize>,
+    ranking: Vec<usize>,
+    snakes: Vec<SnakeRecord>,
+}
+
+fn run_match(batch_config: &BatchConfig, match_number: usize) -> Result<MatchRecord, Error> {
+    let world_config = batch_config.world_config(match_number)?;
+    let seed = world_config.seed;
+    let mut game_match = Game::new_match(world_config)?;
+    let mut ticks = 0;
+    while !game_match.is_over() && ticks < batch_config.max_ticks {
+        game_match.step();
+        ticks += 1;
+    }
+    let game_result = game_match.result();
+    let match_result = game_result.match_result;
+    let snakes = batch_config
+        .bots
+        .iter()
+        .enumerate()
+        .map(|(snake_number, bot)| {
+            let stats = game_result
+                .snakes_stats
+                .get(&snake_number)
+                .copied()
+                .unwrap_or_default();
+            SnakeRecord {
+                number: snake_number,
+                bot: bot.get_name(),
+                score: stats.score(),
+                stats,
+            }
+        })
+        .collect();
+    Ok(MatchRecord {
+        match_number,
+        seed,
+        ticks: game_result.ticks,
+        end: match_result.end,
+        winner: match match_result.winners.as_slice() {
+            [winner] => Some(*winner),
+            _ => None,
+        },
+        winners: match_result.winners,
+        ranking: match_result.ranking,
+        snakes,
+    })
+}
+
+fn run_batch(batch_config: BatchConfig, output: &mut dyn Write) -> Result<(), Error> {
+    let matches = batch_config.matches;
+    let threads = batch_config.threads.clamp(1, matches.max(1));
+    let batch_config = Arc::new(batch_config);
+    let next_match = Arc::new(AtomicUsize::new(0));
+    let (sender, receiver) = mpsc::channel();
+    let workers: Vec<thread::JoinHandle<()>> = (0..threads)
+        .map(|_| {
+            let batch_config = batch_config.clone();
+            let next_match = next_match.clone();
+            let sender = sender.clone();
+            thread::spawn(move || loop {
+                let match_number = next_match.fetch_add(1, Ordering::SeqCst);
+                if match_number >= matches {
+                    break;
+                }
+                let record = run_match(&batch_config, match_number);
+                if sender.send((match_number, record)).is_err() {
+                    break;
+                }
+            })
+        })
+        .collect();
+    drop(sender);
+    let result = write_records(receiver, output);
+    next_match.store(matches, Ordering::SeqCst);
+    for worker in workers {
+        let _ = worker.join();
+    }
+    result
+}
+
+fn write_records(
+    receiver: mpsc::Receiver<(usize, Result<MatchRecord, Error>)>,
+    output: &mut dyn Write,
+) -> Result<(), Error> {
+    let mut pending_records = BTreeMap::new();
+    let mut next_record = 0;
+    for (match_number, record) in receiver {
+        pending_records.insert(match_number, record);
+        while let Some(record) = pending_records.remove(&next_record) {
+            writeln!(output, "{}", serde_json::to_string(&record?)?)?;
+            next_record += 1;
+        }
+        output.flush()?;
+    }
+    Ok(())
+}
+
+fn run_tournament(batch_config: BatchConfig, results_path: &str) -> Result<(), Error> {
+    let tournament_config = match &batch_config.tournament {
+        Some(tournament_config) => tournament_config.clone(),
+        None => return Ok(()),
+    };
+    let tournament = Tournament::new(
+        batch_config.base_world_config(),
+        tournament_config,
+        batch_config.entrants(),
+    );
+    let tournament_result = tournament.run()?;
+    println!(
+        "{:>4}  {:<40} {:>8} {:>7} {:>5} {:>5} {:>5} {:>5}",
+        "rank", "bot", "rating", "points", "games", "wins", "draws", "losses"
+    );
+    for (rank, standing) in tournament_result.leaderboard.iter().enumerate() {
+        println!(
+            "{:>4}  {:<40} {:>8.1} {:>7.1} {:>5} {:>5} {:>5} {:>5}",
+            rank + 1,
+            standing.name,
+            standing.rating,
+            standing.points,
+            standing.games,
+            standing.wins,
+            standing.draws,
+            standing.losses
+        );
+    }
+    tournament_result.save(results_path)?;
+    println!("results written to {}", results_path);
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let mut config_path = None;
+    let mut output_path = None;
+    let mut matches = None;
+    let mut threads = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--output" => output_path = args.next(),
+            "--matches" => matches = args.next().and_then(|value| value.parse().ok()),
+            "--threads" => threads = args.next().and_then(|value| value.parse().ok()),
+            _ => config_path = Some(arg),
+        }
+    }
+    let mut batch_config = match config_path.map(BatchConfig::load) {
+        Some(Ok(batch_config)) => batch_config,
+        Some(Err(err)) => {
+            eprintln!("{}", err);
+            return ExitCode::FAILURE;
+        }
+        None => {
+            eprintln!(
+                "usage: batch-snake <config.json> [--matches N] [--threads N] [--output PATH]"
+            );
+            return ExitCode::FAILURE;
+        }
+    };
+    if let Some(matches) = matches {
+        batch_config.matches = matches;
+    }
+    if let Some(threads) = threads {
+        batch_config.threads = threads;
+    }
+    let result = if batch_config.tournament.is_some() {
+        let results_path = output_path.unwrap_or(DEFAULT_TOURNAMENT_RESULTS_PATH.to_string());
+        run_tournament(batch_config, &results_path)
+    } else {
+        match output_path.map(File::create) {
+            Some(Ok(file)) => run_batch(batch_config, &mut BufWriter::new(file)),
+            Some(Err(err)) => Err(Error::from(err)),
+            None => run_batch(batch_config, &mut io::stdout().lock()),
+        }
+    };
+    exit_code(result)
+}
+
+fn exit_code(result: Result<(), Error>) -> ExitCode {
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("{}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::Value;
+
+    fn batch_config(bots: &str, matches: usize, threads: usize) -> BatchConfig {
+        let batch_config_json = format!(
+            r#"{{"world":{{"rules":{{"world_size":[12,12],"eat_count":1,"base_snake_tail_size":2}},"seed":100}},"bots":{bots},"matches":{matches},"threads":{threads},"max_ticks":40}}"#
+        );
+        serde_json::from_str(&batch_config_json).unwrap()
+    }
+
+    #[test]
+    fn records_are_written_in_match_order() {
+        let batch_config = batch_config(r#"[{"type":"greedy"},{"type":"greedy"}]"#, 8, 4);
+        let mut output = Vec::new();
+        run_batch(batch_config, &mut output).unwrap();
+        let records: Vec<Value> = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(records.len(), 8);
+        for (match_number, record) in records.iter().enumerate() {
+            assert_eq!(record["match_number"], match_number);
+            assert_eq!(record["seed"], 100 + match_number as u64);
+            assert!(record["ticks"].as_u64().is_some());
+            assert!(record.get("winner").is_some());
+            let snakes = record["snakes"].as_array().unwrap();
+            assert_eq!(snakes.len(), 2);
+            for snake in snakes {
+                assert!(snake["stats"].is_object());
+            }
+        }
+    }
+
+    #[test]
+    fn failing_match_exits_with_failure() {
+        let bots = r#"[{"type":"greedy"},{"type":"process","command":["/nonexistent/bot"]}]"#;
+        let batch_config = batch_config(bots, 6, 3);
+        let mut output = Vec::new();
+        let result = run_batch(batch_config, &mut output);
+        assert!(matches!(result, Err(Error::Process(_))));
+        assert!(output.is_empty());
+        assert_eq!(exit_code(result), ExitCode::FAILURE);
+    }
+}
@@ -31,24 +31,38 @@ where
         self.next_node.as_ref().map(|n| n.as_ref())
     }
     pub fn set_next_node(&mut self, next_node: Option<Node<V>>) {
-        self.next_node = next_node.map(|n| Box::new(n));
+        self.next_node = next_node.map(Box::new);
     }
     pub fn all_nodes_values(&self) -> Vec<V> {
-        let mut child_nodes_values = match self.next_node.as_ref() {
-            Some(next_node) => next_node.all_nodes_values(),
-            None => Vec::new(),
-        };
-        child_nodes_values.push(self.value);
-        child_nodes_values
+        let mut nodes_values = Vec::new();
+        let mut node = Some(self);
+        while let Some(current_node) = node {
+            nodes_values.push(current_node.value);
+            node = current_node.get_next_node();
+        }
+        nodes_values.reverse();
+        nodes_values
     }
     pub fn recursive_run<F>(&mut self, mut entrance: F)
     where
         F: FnMut(&mut Node<V>),
     {
-        entrance(self);
-        match self.get_next_node_mut() {
-            Some(next_node) => next_node.recursive_run(entrance),
-            None => {}
+        let mut node = Some(self);
+        while let Some(current_node) = node {
+            entrance(current_node);
+            node = current_node.get_next_node_mut();
+        }
+    }
+}
+
+impl<V> Drop for Node<V>
+where
+    V: Copy + Hash + Eq,
+{
+    fn drop(&mut self) {
+        let mut next_node = self.next_node.take();
+        while let Some(mut node) = next_node {
+            next_node = node.next_node.take();
         }
     }
 }
@@ -1,7 +1,10 @@
+use crate::direction::Direction;
+use serde::{Deserialize, Serialize};
+
 use std::hash::Hash;
 use std::ops::{Add, Sub};
 
-#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Point<N>
 where
     N: Add<Output = N> + Sub<Output = N> + Copy + Eq + Hash,
@@ -24,3 +27,50 @@ where
         self.y
     }
 }
+
+impl<N> Point<N>
+where
+    N: Add<Output = N> + Sub<Output = N> + Copy + Eq + Hash + From<u8>,
+{
+    pub fn moved_to(&self, direction: Direction, wrap_size: Option<(N, N)>) -> Self {
+        let zero = N::from(0);
+        let one = N::from(1);
+        let (mut x, mut y) = (self.x, self.y);
+        match (direction, wrap_size) {
+            (Direction::Right, Some((width, _))) if x + one == width => x = zero,
+            (Direction::Left, Some((width, _))) if x == zero => x = width - one,
+            (Direction::Down, Some((_, height))) if y + one == height => y = zero,
+            (Direction::Up, Some((_, height))) if y == zero => y = height - one,
+            (Direction::Right, _) => x = x + one,
+            (Direction::Left, _) => x = x - one,
+            (Direction::Down, _) => y = y + one,
+            (Direction::Up, _) => y = y - one,
+        }
+        Self::new(x, y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn moved_to_steps_one_cell() {
+        let point = Point::new(3u8, 3);
+        assert_eq!(point.moved_to(Direction::Right, None), Point::new(4, 3));
+        assert_eq!(point.moved_to(Direction::Left, None), Point::new(2, 3));
+        assert_eq!(point.moved_to(Direction::Down, None), Point::new(3, 4));
+        assert_eq!(point.moved_to(Direction::Up, None), Point::new(3, 2));
+    }
+
+    #[test]
+    fn moved_to_wraps_across_each_edge() {
+        let wrap_size = Some((5u8, 4));
+        let moved_to = |x, y, direction| Point::new(x, y).moved_to(direction, wrap_size);
+        assert_eq!(moved_to(4, 2, Direction::Right), Point::new(0, 2));
+        assert_eq!(moved_to(0, 2, Direction::Left), Point::new(4, 2));
+        assert_eq!(moved_to(2, 3, Direction::Down), Point::new(2, 0));
+        assert_eq!(moved_to(2, 0, Direction::Up), Point::new(2, 3));
+        assert_eq!(moved_to(2, 2, Direction::Right), Point::new(3, 2));
+    }
+}
@@ -0,0 +1,42 @@
+pub const SPLIT_MIX_RNG_KIND: &str = "split_mix";
+
+pub trait Rng: Send {
+    fn get_kind(&self) -> &str;
+    fn next_u64(&mut self) -> u64;
+    fn get_state(&self) -> u64;
+    fn set_state(&mut self, state: u64);
+    fn rand_in_range(&mut self, a: i64, b: i64) -> i64 {
+        let m = (b - a + 1) as u64;
+        a + (self.next_u64() % m) as i64
+    }
+}
+
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub struct SplitMixRng {
+    state: u64,
+}
+
+impl SplitMixRng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+}
+
+impl Rng for SplitMixRng {
+    fn get_kind(&self) -> &str {
+        SPLIT_MIX_RNG_KIND
+    }
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+    fn get_state(&self) -> u64 {
+        self.state
+    }
+    fn set_state(&mut self, state: u64) {
+        self.state = state;
+    }
+}
@@ -25,6 +25,9 @@ where
     pub fn set_layer(&mut self, layer_key: L, layer: HashSet<Point<N>>) {
         self.layers.insert(layer_key, layer);
     }
+    pub fn get_layer(&self, layer_key: &L) -> Option<&HashSet<Point<N>>> {
+        self.layers.get(layer_key)
+    }
     pub fn remove_layer(&mut self, layer_key: &L) {
         self.layers.remove(layer_key);
     }
@@ -59,3 +62,13 @@ where
         map
     }
 }
+
+impl<L, N> Default for World<L, N>
+where
+    L: Hash + Eq + Copy,
+    N: Add<Output = N> + Sub<Output = N> + Copy + Eq + Hash,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
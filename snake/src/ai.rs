@@ -0,0 +1,118 @@
+pub mod battlesnake;
+pub mod greedy;
+pub mod hamiltonian;
+pub mod pathfinding;
+pub mod process;
+
+use super::components::direction::Direction;
+use super::components::point::Point;
+use super::world::{FoodKind, ObjectType, WorldView};
+use super::AreaSize;
+
+use std::collections::{HashSet, VecDeque};
+
+pub(crate) fn next_point(
+    world_view: &WorldView,
+    point: &Point<AreaSize>,
+    direction: Direction,
+) -> Option<Point<AreaSize>> {
+    let rules = world_view.get_rules();
+    let (width, height) = rules.world_size;
+    let wrap_size = rules.wrap_size();
+    if wrap_size.is_none() {
+        let is_outside = match direction {
+            Direction::Right => point.x() + 1 >= width,
+            Direction::Left => point.x() == 0,
+            Direction::Down => point.y() + 1 >= height,
+            Direction::Up => point.y() == 0,
+        };
+        if is_outside {
+            return None;
+        }
+    }
+    Some(point.moved_to(direction, wrap_size))
+}
+
+pub(crate) fn is_blocked(world_view: &WorldView, point: &Point<AreaSize>) -> bool {
+    world_view
+        .get_world_mask()
+        .point_occurrences(point)
+        .iter()
+        .any(|object| !matches!(object, ObjectType::Eat(_)))
+}
+
+pub(crate) fn distance(
+    world_view: &WorldView,
+    from: &Point<AreaSize>,
+    to: &Point<AreaSize>,
+) -> usize {
+    let rules = world_view.get_rules();
+    let axis_distance = |a: AreaSize, b: AreaSize, size: AreaSize| {
+        let direct = a.abs_diff(b) as usize;
+        match rules.wrap_size() {
+            Some(_) => direct.min(size as usize - direct),
+            None => direct,
+        }
+    };
+    axis_distance(from.x(), to.x(), rules.world_size.0)
+        + axis_distance(from.y(), to.y(), rules.world_size.1)
+}
+
+pub(crate) fn food_targets(world_view: &WorldView) -> Vec<Point<AreaSize>> {
+    let eat_points = world_view.get_eat_points();
+    let has_good_food = eat_points.values().any(|kind| *kind != FoodKind::Poison);
+    let mut targets: Vec<Point<AreaSize>> = eat_points
+        .iter()
+        .filter(|(_, kind)| !has_good_food || **kind != FoodKind::Poison)
+        .map(|(point, _)| *point)
+        .collect();
+    targets.sort_by_key(|point| (point.y(), point.x()));
+    targets
+}
+
+pub(crate) fn neighbors(
+    world_view: &WorldView,
+    point: &Point<AreaSize>,
+) -> Vec<(Direction, Point<AreaSize>)> {
+    Direction::ALL
+        .iter()
+        .filter_map(|direction| {
+            let neighbor = next_point(world_view, point, *direction)?;
+            Some((*direction, neighbor))
+        })
+        .collect()
+}
+
+pub(crate) fn obstacles(world_view: &WorldView) -> HashSet<Point<AreaSize>> {
+    let mut obstacles = world_view.get_border_points().clone();
+    for snake_info in world_view.get_snakes_info().values() {
+        obstacles.extend(snake_info.get_snake().body_parts_points(true));
+    }
+    let world_mask = world_view.get_world_mask();
+    for snake_number in world_view.get_snakes_numbers() {
+        let layer = world_mask.get_layer(&ObjectType::Snake(*snake_number));
+        obstacles.extend(layer.into_iter().flatten());
+    }
+    obstacles
+}
+
+pub(crate) fn flood_fill_area(
+    world_view: &WorldView,
+    start: &Point<AreaSize>,
+    obstacles: &HashSet<Point<AreaSize>>,
+    limit: usize,
+) -> usize {
+    let mut visited = HashSet::from([*start]);
+    let mut queue = VecDeque::from([*start]);
+    while let Some(point) = queue.pop_front() {
+        if visited.len() >= limit {
+            break;
+        }
+        for (_, neighbor) in neighbors(world_view, &point) {
+            if !obstacles.contains(&neighbor) && visited.insert(neighbor) {
+                queue.push_back(neighbor);
+            }
+        }
+    }
+    visited.len()
+}
@@ -0,0 +1,341 @@
+use super::super::battlesnake::{parse_direction, GameState, MoveResponse};
+use super::super::components::direction::Direction;
+use super::super::world::{EatType, SnakeController, SnakeInfo, WorldView};
+
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::{Duration, Instant};
+
+pub const START_PATH: &str = "/start";
+pub const MOVE_PATH: &str = "/move";
+pub const END_PATH: &str = "/end";
+
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    Json(serde_json::Error),
+    Url,
+    Status(u16),
+    Response,
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(err: serde_json::Error) -> Self {
+        Self::Json(err)
+    }
+}
+
+struct DeadlineStream {
+    stream: TcpStream,
+    deadline: Instant,
+}
+
+impl Read for DeadlineStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let remaining = self.deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Err(io::ErrorKind::TimedOut.into());
+        }
+        self.stream.set_read_timeout(Some(remaining))?;
+        self.stream.read(buf)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Endpoint {
+    host: String,
+    port: u16,
+    path: String,
+}
+
+impl Endpoint {
+    fn parse(url: &str) -> Result<Self, Error> {
+        let address = url.strip_prefix("http://").ok_or(Error::Url)?;
+        let (authority, path) = match address.find('/') {
+            Some(index) => (&address[..index], address[index..].trim_end_matches('/')),
+            None => (address, ""),
+        };
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) => (host, port.parse().map_err(|_| Error::Url)?),
+            None => (authority, 80),
+        };
+        if host.is_empty() {
+            return Err(Error::Url);
+        }
+        Ok(Self {
+            host: host.to_string(),
+            port,
+            path: path.to_string(),
+        })
+    }
+    fn post(&self, path: &str, body: &str, timeout: Duration) -> Result<String, Error> {
+        let deadline = Instant::now() + timeout;
+        let remaining = || {
+            deadline
+                .saturating_duration_since(Instant::now())
+                .max(Duration::from_millis(1))
+        };
+        let address = (self.host.as_str(), self.port)
+            .to_socket_addrs()?
+            .next()
+            .ok_or(Error::Url)?;
+        let mut stream = TcpStream::connect_timeout(&address, remaining())?;
+        stream.set_nodelay(true)?;
+        stream.set_write_timeout(Some(remaining()))?;
+        let request = format!(
+            "POST {}{} HTTP/1.1\r\nHost: {}:{}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            self.path,
+            path,
+            self.host,
+            self.port,
+            body.len(),
+            body
+        );
+        stream.write_all(request.as_bytes())?;
+        stream.flush()?;
+        let mut reader = BufReader::new(DeadlineStream { stream, deadline });
+        let mut status_line = String::new();
+        reader.read_line(&mut status_line)?;
+        let status: u16 = status_line
+            .split_whitespace()
+            .nth(1)
+            .and_then(|status| status.parse().ok())
+            .ok_or(Error::Response)?;
+        let mut content_length = None;
+        let mut is_chunked = false;
+        loop {
+            let mut header = String::new();
+            if reader.read_line(&mut header)? == 0 {
+                break;
+            }
+            let header = header.trim_end();
+            if header.is_empty() {
+                break;
+            }
+            if let Some((name, value)) = header.split_once(':') {
+                let value = value.trim();
+                if name.eq_ignore_ascii_case("content-length") {
+                    content_length = value.parse::<usize>().ok();
+                } else if name.eq_ignore_ascii_case("transfer-encoding") {
+                    is_chunked = value.eq_ignore_ascii_case("chunked");
+                }
+            }
+        }
+        if !(200..300).contains(&status) {
+            return Err(Error::Status(status));
+        }
+        let mut body = Vec::new();
+        if is_chunked {
+            loop {
+                let mut size_line = String::new();
+                reader.read_line(&mut size_line)?;
+                let size_text = size_line.trim().split(';').next().unwrap_or("");
+                let size = usize::from_str_radix(size_text, 16).map_err(|_| Error::Response)?;
+                if size == 0 {
+                    break;
+                }
+                let mut chunk = vec![0; size + 2];
+                reader.read_exact(&mut chunk)?;
+                body.extend_from_slice(&chunk[..size]);
+            }
+        } else if let Some(content_length) = content_length {
+            body.resize(content_length, 0);
+            reader.read_exact(&mut body)?;
+        } else {
+            reader.read_to_end(&mut body)?;
+        }
+        String::from_utf8(body).map_err(|_| Error::Response)
+    }
+}
+
+pub struct BattlesnakeSnakeController {
+    endpoint: Endpoint,
+    timeout: Duration,
+    game_id: String,
+    last_state: Option<GameState>,
+    shout: String,
+    is_started: bool,
+}
+
+impl BattlesnakeSnakeController {
+    pub fn new(url: &str, game_id: &str, timeout: Duration) -> Result<Self, Error> {
+        Ok(Self {
+            endpoint: Endpoint::parse(url)?,
+            timeout,
+            game_id: game_id.to_string(),
+            last_state: None,
+            shout: String::new(),
+            is_started: false,
+        })
+    }
+    pub fn get_shout(&self) -> &String {
+        &self.shout
+    }
+    fn request_move(&mut self, state: &GameState) -> Result<Direction, Error> {
+        let body = serde_json::to_string(state)?;
+        let response = self.endpoint.post(MOVE_PATH, &body, self.timeout)?;
+        let response: MoveResponse = serde_json::from_str(&response)?;
+        self.shout = response.shout;
+        parse_direction(&response.direction).ok_or(Error::Response)
+    }
+    fn notify(&self, path: &str) {
+        if let Some(state) = &self.last_state {
+            if let Ok(body) = serde_json::to_string(state) {
+                let _ = self.endpoint.post(path, &body, self.timeout);
+            }
+        }
+    }
+}
+
+impl SnakeController for BattlesnakeSnakeController {
+    fn snake_will_burn(&mut self, _: &WorldView) {}
+    fn snake_did_burn(&mut self, self_info: &SnakeInfo, world_view: &WorldView) {
+        self.last_state =
+            GameState::from_world_view(world_view, self_info, &self.game_id, self.timeout).ok();
+        if !self.is_started {
+            self.is_started = true;
+            self.notify(START_PATH);
+        }
+    }
+    fn snake_will_move(&mut self, self_info: &SnakeInfo, world_view: &WorldView) -> Direction {
+        let fallback_direction = self_info.get_direction().unwrap_or(Direction::Right);
+        let state =
+            GameState::from_world_view(world_view, self_info, &self.game_id, self.timeout).ok();
+        let direction = match &state {
+            Some(state) => self.request_move(state).ok(),
+            None => None,
+        };
+        self.last_state = state;
+        direction.unwrap_or(fallback_direction)
+    }
+    fn snake_did_move(&mut self, _: &SnakeInfo, _: &WorldView) {}
+    fn snake_will_eat(&mut self, _: EatType, _: &SnakeInfo, _: &WorldView) {}
+    fn snake_did_eat(&mut self, _: EatType, _: &SnakeInfo, _: &WorldView) {}
+    fn snake_will_died(&mut self, _: &SnakeInfo, _: &WorldView) {}
+    fn snake_did_died(&mut self, world_view: &WorldView) {
+        let lives = self
+            .last_state
+            .as_ref()
+            .and_then(|state| state.you.id.parse::<usize>().ok())
+            .and_then(|snake_number| world_view.get_snakes_lives().get(&snake_number));
+        if lives.is_none_or(|lives| *lives == 0) {
+            self.notify(END_PATH);
+            self.last_state = None;
+            self.is_started = false;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::fixtures;
+    use super::super::super::world::World;
+    use super::*;
+
+    use std::net::TcpListener;
+    use std::sync::mpsc;
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+
+    fn read_request(stream: &mut TcpStream) -> String {
+        let mut reader = BufReader::new(stream);
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line).unwrap();
+        let mut content_length = 0;
+        loop {
+            let mut header = String::new();
+            reader.read_line(&mut header).unwrap();
+            let header = header.trim_end();
+            if header.is_empty() {
+                break;
+            }
+            if let Some((name, value)) = header.split_once(':') {
+                if name.eq_ignore_ascii_case("content-length") {
+                    content_length = value.trim().parse().unwrap();
+                }
+            }
+        }
+        let mut body = vec![0; content_length];
+        reader.read_exact(&mut body).unwrap();
+        request_line.split_whitespace().nth(1).unwrap().to_string()
+    }
+
+    #[test]
+    fn posts_start_once_moves_and_end() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let (sender, paths_receiver) = mpsc::channel();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let path = read_request(&mut stream);
+                let body = match path.as_str() {
+                    MOVE_PATH => r#"{"move":"up","shout":"up we go"}"#,
+                    _ => "{}",
+                };
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                stream.write_all(response.as_bytes()).unwrap();
+                let is_end = path == END_PATH;
+                sender.send(path).unwrap();
+                if is_end {
+                    break;
+                }
+            }
+        });
+        let mut config = fixtures::config(12, 1);
+        config.rules.lives = 2;
+        config.rules.respawn_delay = 0;
+        let controller =
+            BattlesnakeSnakeController::new(&url, "mock", Duration::from_secs(5)).unwrap();
+        config
+            .snakes_controllers
+            .insert(0, Arc::new(Mutex::new(controller)));
+        let mut world = World::new(config).unwrap();
+        world.tick(true);
+        while !world.is_match_over() && world.get_tick_number() < 100 {
+            world.tick(false);
+        }
+        assert!(world.is_match_over());
+        let mut paths = Vec::new();
+        while let Ok(path) = paths_receiver.recv_timeout(Duration::from_secs(5)) {
+            paths.push(path);
+        }
+        assert_eq!(paths.first().map(String::as_str), Some(START_PATH));
+        assert_eq!(paths.last().map(String::as_str), Some(END_PATH));
+        assert_eq!(paths.iter().filter(|path| *path == START_PATH).count(), 1);
+        assert_eq!(paths.iter().filter(|path| *path == END_PATH).count(), 1);
+        assert!(paths.iter().any(|path| path == MOVE_PATH));
+    }
+
+    #[test]
+    fn trickling_response_hits_overall_deadline() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            read_request(&mut stream);
+            let _ = stream.write_all(b"HTTP/1.1 200 OK\r\n");
+            for _ in 0..40 {
+                thread::sleep(Duration::from_millis(25));
+                if stream.write_all(b"X").is_err() {
+                    break;
+                }
+            }
+        });
+        let endpoint = Endpoint::parse(&url).unwrap();
+        let start_time = Instant::now();
+        let response = endpoint.post(MOVE_PATH, "{}", Duration::from_millis(200));
+        assert!(response.is_err());
+        assert!(start_time.elapsed() < Duration::from_millis(800));
+    }
+}
@@ -0,0 +1,53 @@
+use super::super::components::direction::Direction;
+use super::super::world::{EatType, SnakeController, SnakeInfo, WorldView};
+use super::{distance, food_targets, is_blocked, next_point};
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct GreedySnakeController;
+
+impl GreedySnakeController {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl SnakeController for GreedySnakeController {
+    fn snake_will_burn(&mut self, _: &WorldView) {}
+    fn snake_did_burn(&mut self, _: &SnakeInfo, _: &WorldView) {}
+    fn snake_will_move(&mut self, self_info: &SnakeInfo, world_view: &WorldView) -> Direction {
+        let head_point = self_info.get_snake().head_point();
+        let current_direction = *self_info.get_direction();
+        let targets = food_targets(world_view);
+        let mut best_move: Option<(usize, Direction)> = None;
+        for direction in Direction::ALL {
+            if Some(direction.reverse()) == current_direction {
+                continue;
+            }
+            let point = match next_point(world_view, &head_point, direction) {
+                Some(point) => point,
+                None => continue,
+            };
+            if is_blocked(world_view, &point) {
+                continue;
+            }
+            let food_distance = targets
+                .iter()
+                .map(|target| distance(world_view, &point, target))
+                .min()
+                .unwrap_or(0);
+            if best_move.is_none_or(|(best_distance, _)| food_distance < best_distance) {
+                best_move = Some((food_distance, direction));
+            }
+        }
+        match (best_move, current_direction) {
+            (Some((_, direction)), _) => direction,
+            (None, Some(direction)) => direction,
+            (None, None) => Direction::Right,
+        }
+    }
+    fn snake_did_move(&mut self, _: &SnakeInfo, _: &WorldView) {}
+    fn snake_will_eat(&mut self, _: EatType, _: &SnakeInfo, _: &WorldView) {}
+    fn snake_did_eat(&mut self, _: EatType, _: &SnakeInfo, _: &WorldView) {}
+    fn snake_will_died(&mut self, _: &SnakeInfo, _: &WorldView) {}
+    fn snake_did_died(&mut self, _: &WorldView) {}
+}
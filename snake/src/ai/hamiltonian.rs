@@ -0,0 +1,268 @@
+use super::super::components::direction::Direction;
+use super::super::components::point::Point;
+use super::super::world::{EatType, SnakeController, SnakeInfo, Topology, WorldView};
+use super::super::AreaSize;
+use super::{flood_fill_area, food_targets, neighbors, obstacles};
+
+use std::collections::{HashMap, HashSet};
+
+const SHORTCUT_MARGIN: usize = 3;
+
+/// Follows a cycle over the whole playfield and only takes shortcuts that
+/// keep the body in cycle order (head, then food, then tail).
+///
+/// Bounded playfields with odd width and height have no Hamiltonian cycle:
+/// there the cycle skips the corner next to its first cell and both cells
+/// share one slot, so the snake tops out one cell short of the playfield.
+/// Levels with walls across the cycle leave the snake without a cycle, and
+/// it just keeps to the largest free area.
+#[derive(Debug, Default, Clone)]
+pub struct HamiltonianSnakeController {
+    cycle: Vec<Point<AreaSize>>,
+    cycle_indexes: HashMap<Point<AreaSize>, usize>,
+    cycle_key: Option<((AreaSize, AreaSize), Topology)>,
+}
+
+impl HamiltonianSnakeController {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn get_cycle(&self) -> &Vec<Point<AreaSize>> {
+        &self.cycle
+    }
+    fn playfield(
+        world_size: (AreaSize, AreaSize),
+        topology: Topology,
+    ) -> (AreaSize, AreaSize, AreaSize) {
+        match topology {
+            Topology::Bounded => (
+                1,
+                world_size.0.saturating_sub(2),
+                world_size.1.saturating_sub(2),
+            ),
+            Topology::Torus => (0, world_size.0, world_size.1),
+        }
+    }
+    fn build_cycle(world_size: (AreaSize, AreaSize), topology: Topology) -> Vec<Point<AreaSize>> {
+        let (offset, width, height) = Self::playfield(world_size, topology);
+        let (columns, rows, is_transposed) = if height % 2 == 0 || topology == Topology::Torus {
+            (width, height, false)
+        } else if width % 2 == 0 {
+            (height, width, true)
+        } else {
+            (width, height, false)
+        };
+        if columns < 3 || rows < 3 {
+            return Vec::new();
+        }
+        let is_odd_board = rows % 2 == 1 && topology == Topology::Bounded;
+        let mut cells = Vec::new();
+        let first_row = if is_odd_board {
+            for column in (1..columns).step_by(2) {
+                cells.extend([(column, 1), (column, 0), (column + 1, 0), (column + 1, 1)]);
+            }
+            2
+        } else {
+            0
+        };
+        for row in first_row..rows {
+            if (row % 2 == 0) != is_odd_board {
+                cells.extend((1..columns).map(|column| (column, row)));
+            } else {
+                cells.extend((1..columns).rev().map(|column| (column, row)));
+            }
+        }
+        cells.extend((first_row.min(1)..rows).rev().map(|row| (0, row)));
+        cells
+            .into_iter()
+            .map(|(column, row)| {
+                if is_transposed {
+                    Point::new(row + offset, column + offset)
+                } else {
+                    Point::new(column + offset, row + offset)
+                }
+            })
+            .collect()
+    }
+    fn update_cycle(&mut self, world_view: &WorldView) {
+        let rules = world_view.get_rules();
+        let cycle_key = Some((rules.world_size, rules.topology));
+        if self.cycle_key != cycle_key {
+            self.cycle = Self::build_cycle(rules.world_size, rules.topology);
+            self.cycle_indexes = self
+                .cycle
+                .iter()
+                .enumerate()
+                .map(|(index, point)| (*point, index))
+                .collect();
+            let (offset, width, height) = Self::playfield(rules.world_size, rules.topology);
+            let corner_point = Point::new(offset, offset);
+            if self.cycle.len() + 1 == usize::from(width) * usize::from(height) {
+                self.cycle_indexes.insert(corner_point, 0);
+            }
+            self.cycle_key = cycle_key;
+        }
+        let is_cycle_blocked = world_view
+            .get_border_points()
+            .iter()
+            .any(|point| self.cycle_indexes.contains_key(point));
+        if is_cycle_blocked {
+            self.cycle.clear();
+            self.cycle_indexes.clear();
+        }
+    }
+    fn cycle_move(
+        &self,
+        world_view: &WorldView,
+        body_points: &[Point<AreaSize>],
+        stomach_size: usize,
+        free_neighbors: &[(Direction, Point<AreaSize>)],
+    ) -> Option<Direction> {
+        let cycle_length = self.cycle.len();
+        let cycle_distance = |from: usize, to: usize| (to + cycle_length - from) % cycle_length;
+        let body_indexes = body_points
+            .iter()
+            .map(|point| self.cycle_indexes.get(point).copied())
+            .collect::<Option<Vec<usize>>>()?;
+        let head_index = *body_indexes.last()?;
+        let tail_index = *body_indexes.first()?;
+        let body_span: usize = body_indexes
+            .windows(2)
+            .map(|pair| cycle_distance(pair[0], pair[1]))
+            .sum();
+        let is_ordered =
+            body_indexes.windows(2).all(|pair| pair[0] != pair[1]) && body_span < cycle_length;
+        let tail_distance = match cycle_distance(head_index, tail_index) {
+            0 => cycle_length,
+            tail_distance => tail_distance,
+        };
+        let food_distance = food_targets(world_view)
+            .iter()
+            .filter_map(|point| self.cycle_indexes.get(point))
+            .map(|food_index| cycle_distance(head_index, *food_index))
+            .min();
+        let allow_shortcuts =
+            (body_points.len() + stomach_size + SHORTCUT_MARGIN) * 2 < cycle_length;
+        let eat_points = world_view.get_eat_points();
+        let mut best_move: Option<((usize, bool, bool), Direction)> = None;
+        for (direction, point) in free_neighbors {
+            let Some(index) = self.cycle_indexes.get(point) else {
+                continue;
+            };
+            let distance = cycle_distance(head_index, *index);
+            let is_food = eat_points.contains_key(point);
+            let growth = stomach_size + usize::from(is_food);
+            let is_allowed = if !is_ordered {
+                distance == 1
+            } else if distance == 1 {
+                distance + growth <= tail_distance
+            } else {
+                allow_shortcuts
+                    && distance + growth + SHORTCUT_MARGIN <= tail_distance
+                    && food_distance.is_some_and(|food_distance| distance <= food_distance)
+            };
+            if !is_allowed {
+                continue;
+            }
+            let is_on_cycle = self.cycle[*index] == *point;
+            let key = (distance, is_food, is_on_cycle);
+            if best_move.is_none_or(|(best_key, _)| key > best_key) {
+                best_move = Some((key, *direction));
+            }
+        }
+        best_move.map(|(_, direction)| direction)
+    }
+}
+
+impl SnakeController for HamiltonianSnakeController {
+    fn snake_will_burn(&mut self, _: &WorldView) {}
+    fn snake_did_burn(&mut self, _: &SnakeInfo, world_view: &WorldView) {
+        self.update_cycle(world_view);
+    }
+    fn snake_will_move(&mut self, self_info: &SnakeInfo, world_view: &WorldView) -> Direction {
+        self.update_cycle(world_view);
+        let snake = self_info.get_snake();
+        let head_point = snake.head_point();
+        let body_points = snake.body_parts_points(true);
+        let mut obstacles: HashSet<Point<AreaSize>> = obstacles(world_view);
+        if let (Some(tail_point), false) = (body_points.first(), snake.is_stomach_not_empty()) {
+            if *tail_point != head_point {
+                obstacles.remove(tail_point);
+            }
+        }
+        let neck_point = body_points
+            .len()
+            .checked_sub(2)
+            .map(|neck| body_points[neck]);
+        let free_neighbors: Vec<(Direction, Point<AreaSize>)> = neighbors(world_view, &head_point)
+            .into_iter()
+            .filter(|(_, point)| !obstacles.contains(point) && Some(*point) != neck_point)
+            .collect();
+        let cycle_move = self.cycle_move(
+            world_view,
+            &body_points,
+            snake.stomach_size(),
+            &free_neighbors,
+        );
+        let limit = body_points.len() * 2;
+        let survival_move = || {
+            free_neighbors
+                .iter()
+                .max_by_key(|(_, point)| flood_fill_area(world_view, point, &obstacles, limit))
+                .map(|(direction, _)| *direction)
+        };
+        match (cycle_move.or_else(survival_move), self_info.get_direction()) {
+            (Some(direction), _) => direction,
+            (None, Some(direction)) => *direction,
+            (None, None) => Direction::Right,
+        }
+    }
+    fn snake_did_move(&mut self, _: &SnakeInfo, _: &WorldView) {}
+    fn snake_will_eat(&mut self, _: EatType, _: &SnakeInfo, _: &WorldView) {}
+    fn snake_did_eat(&mut self, _: EatType, _: &SnakeInfo, _: &WorldView) {}
+    fn snake_will_died(&mut self, _: &SnakeInfo, _: &WorldView) {}
+    fn snake_did_died(&mut self, _: &WorldView) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::fixtures;
+    use super::super::super::world::{MatchEnd, WinCondition, World};
+    use super::*;
+
+    use std::sync::{Arc, Mutex};
+
+    fn fill_board(world_size: AreaSize, topology: Topology, target_length: usize) {
+        let mut config = fixtures::config(world_size, 7);
+        config.rules.base_snake_tail_size = 1;
+        config.rules.topology = topology;
+        config.rules.win_conditions = vec![WinCondition::TargetLength(target_length)];
+        let controller = Arc::new(Mutex::new(HamiltonianSnakeController::new()));
+        config.snakes_controllers.insert(0, controller);
+        let mut world = World::new(config).unwrap();
+        world.tick(true);
+        while !world.is_match_over() && world.get_tick_number() < 100_000 {
+            world.tick(false);
+        }
+        let target_end = MatchEnd::Win(WinCondition::TargetLength(target_length));
+        assert_eq!(*world.get_match_end(), Some(target_end));
+        let snake_stats = world.get_snakes_stats()[&0];
+        assert_eq!(snake_stats.tails_cut, 0);
+        assert_eq!(snake_stats.deaths, 0);
+    }
+
+    #[test]
+    fn fills_even_board() {
+        fill_board(10, Topology::Bounded, 64);
+    }
+
+    #[test]
+    fn fills_odd_torus() {
+        fill_board(11, Topology::Torus, 121);
+    }
+
+    #[test]
+    fn fills_odd_board_but_one_cell() {
+        fill_board(11, Topology::Bounded, 80);
+    }
+}
@@ -0,0 +1,193 @@
+use super::super::components::direction::Direction;
+use super::super::components::point::Point;
+use super::super::world::{EatType, SnakeController, SnakeInfo, WorldView};
+use super::super::AreaSize;
+use super::{distance, flood_fill_area, food_targets, neighbors, obstacles};
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct PathfindingSnakeController;
+
+impl PathfindingSnakeController {
+    pub fn new() -> Self {
+        Self
+    }
+    fn find_path(
+        world_view: &WorldView,
+        start: &Point<AreaSize>,
+        targets: &[Point<AreaSize>],
+        obstacles: &HashSet<Point<AreaSize>>,
+    ) -> Option<Vec<Point<AreaSize>>> {
+        let heuristic = |point: &Point<AreaSize>| {
+            targets
+                .iter()
+                .map(|target| distance(world_view, point, target))
+                .min()
+        };
+        let mut open = BinaryHeap::new();
+        let mut came_from = HashMap::<Point<AreaSize>, Point<AreaSize>>::new();
+        let mut costs = HashMap::from([(*start, 0)]);
+        open.push(Reverse((heuristic(start)?, 0, start.y(), start.x())));
+        while let Some(Reverse((_, cost, y, x))) = open.pop() {
+            let point = Point::new(x, y);
+            if costs.get(&point).is_some_and(|best_cost| cost > *best_cost) {
+                continue;
+            }
+            if point != *start && targets.contains(&point) {
+                let mut path = vec![point];
+                while let Some(previous) = came_from.get(path.last()?) {
+                    if previous == start {
+                        break;
+                    }
+                    path.push(*previous);
+                }
+                path.reverse();
+                return Some(path);
+            }
+            for (_, neighbor) in neighbors(world_view, &point) {
+                if obstacles.contains(&neighbor) {
+                    continue;
+                }
+                let neighbor_cost = cost + 1;
+                if costs
+                    .get(&neighbor)
+                    .is_none_or(|best_cost| neighbor_cost < *best_cost)
+                {
+                    costs.insert(neighbor, neighbor_cost);
+                    came_from.insert(neighbor, point);
+                    let estimate = neighbor_cost + heuristic(&neighbor)?;
+                    open.push(Reverse((
+                        estimate,
+                        neighbor_cost,
+                        neighbor.y(),
+                        neighbor.x(),
+                    )));
+                }
+            }
+        }
+        None
+    }
+    fn is_safe_path(
+        world_view: &WorldView,
+        body_points: &[Point<AreaSize>],
+        path: &[Point<AreaSize>],
+        obstacles: &HashSet<Point<AreaSize>>,
+    ) -> bool {
+        let length = body_points.len() + 1;
+        let virtual_body: Vec<Point<AreaSize>> = body_points.iter().chain(path).copied().collect();
+        let virtual_body = &virtual_body[virtual_body.len().saturating_sub(length)..];
+        let mut virtual_obstacles = obstacles.clone();
+        for point in body_points {
+            virtual_obstacles.remove(point);
+        }
+        virtual_obstacles.extend(virtual_body.iter().skip(1));
+        match virtual_body.last() {
+            Some(head_point) => {
+                flood_fill_area(world_view, head_point, &virtual_obstacles, length + 1) > length
+            }
+            None => false,
+        }
+    }
+    fn direction_to(
+        world_view: &WorldView,
+        from: &Point<AreaSize>,
+        to: &Point<AreaSize>,
+    ) -> Option<Direction> {
+        neighbors(world_view, from)
+            .into_iter()
+            .find(|(_, neighbor)| neighbor == to)
+            .map(|(direction, _)| direction)
+    }
+}
+
+impl SnakeController for PathfindingSnakeController {
+    fn snake_will_burn(&mut self, _: &WorldView) {}
+    fn snake_did_burn(&mut self, _: &SnakeInfo, _: &WorldView) {}
+    fn snake_will_move(&mut self, self_info: &SnakeInfo, world_view: &WorldView) -> Direction {
+        let snake = self_info.get_snake();
+        let head_point = snake.head_point();
+        let body_points = snake.body_parts_points(true);
+        let mut obstacles = obstacles(world_view);
+        if let (Some(tail_point), false) = (body_points.first(), snake.is_stomach_not_empty()) {
+            if *tail_point != head_point {
+                obstacles.remove(tail_point);
+            }
+        }
+        for other_info in world_view.get_snakes_info().values() {
+            let other_head_point = other_info.get_snake().head_point();
+            let other_length = other_info.get_snake().body_parts_points(true).len();
+            if other_head_point != head_point && other_length >= body_points.len() {
+                let risky_points = neighbors(world_view, &other_head_point);
+                obstacles.extend(risky_points.into_iter().map(|(_, point)| point));
+            }
+        }
+        let targets: Vec<Point<AreaSize>> = food_targets(world_view)
+            .into_iter()
+            .filter(|point| !obstacles.contains(point))
+            .collect();
+        if let Some(path) = Self::find_path(world_view, &head_point, &targets, &obstacles) {
+            if Self::is_safe_path(world_view, &body_points, &path, &obstacles) {
+                if let Some(direction) = Self::direction_to(world_view, &head_point, &path[0]) {
+                    return direction;
+                }
+            }
+        }
+        let tail_point = body_points
+            .first()
+            .filter(|point| **point != head_point && !obstacles.contains(point));
+        if let Some(tail_point) = tail_point {
+            let tail_targets = [*tail_point];
+            if let Some(path) = Self::find_path(world_view, &head_point, &tail_targets, &obstacles)
+            {
+                if let Some(direction) = Self::direction_to(world_view, &head_point, &path[0]) {
+                    return direction;
+                }
+            }
+        }
+        let limit = body_points.len() * 2;
+        let survival_move = neighbors(world_view, &head_point)
+            .into_iter()
+            .filter(|(_, point)| !obstacles.contains(point))
+            .max_by_key(|(_, point)| flood_fill_area(world_view, point, &obstacles, limit));
+        match (survival_move, self_info.get_direction()) {
+            (Some((direction, _)), _) => direction,
+            (None, Some(direction)) => *direction,
+            (None, None) => Direction::Right,
+        }
+    }
+    fn snake_did_move(&mut self, _: &SnakeInfo, _: &WorldView) {}
+    fn snake_will_eat(&mut self, _: EatType, _: &SnakeInfo, _: &WorldView) {}
+    fn snake_did_eat(&mut self, _: EatType, _: &SnakeInfo, _: &WorldView) {}
+    fn snake_will_died(&mut self, _: &SnakeInfo, _: &WorldView) {}
+    fn snake_did_died(&mut self, _: &WorldView) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::fixtures;
+    use super::super::super::world::World;
+    use super::*;
+
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn path_never_enters_obstacle_targets() {
+        let mut config = fixtures::config(12, 1);
+        let controller = Arc::new(Mutex::new(PathfindingSnakeController::new()));
+        config.snakes_controllers.insert(0, controller);
+        let mut world = World::new(config).unwrap();
+        let world_view = world.tick(true);
+        let start = Point::new(5, 5);
+        let target = Point::new(7, 5);
+        let mut obstacles = HashSet::new();
+        let path =
+            PathfindingSnakeController::find_path(&world_view, &start, &[target], &obstacles);
+        assert_eq!(path, Some(vec![Point::new(6, 5), target]));
+        obstacles.insert(target);
+        let path =
+            PathfindingSnakeController::find_path(&world_view, &start, &[target], &obstacles);
+        assert_eq!(path, None);
+    }
+}
@@ -0,0 +1,304 @@
+use super::super::components::direction::Direction;
+use super::super::components::point::Point;
+use super::super::snapshot::sorted_points;
+use super::super::world::{EatType, FoodKind, SnakeController, SnakeInfo, WorldView};
+use super::super::AreaSize;
+
+use serde::{Deserialize, Serialize};
+use std::io::{self, BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::{Duration, Instant};
+
+pub const PROTOCOL_VERSION: u32 = 2;
+pub const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    Json(serde_json::Error),
+    Handshake,
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(err: serde_json::Error) -> Self {
+        Self::Json(err)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnakeState {
+    pub number: usize,
+    pub body: Vec<Point<AreaSize>>,
+    pub direction: Option<Direction>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Message {
+    Start {
+        protocol: u32,
+    },
+    Tick {
+        turn: usize,
+        world_size: (AreaSize, AreaSize),
+        you: SnakeState,
+        snakes: Vec<SnakeState>,
+        food: Vec<(Point<AreaSize>, FoodKind)>,
+        borders: Vec<Point<AreaSize>>,
+    },
+    Died,
+    GameOver,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Reply {
+    Ready {
+        #[serde(default)]
+        name: String,
+    },
+    Move {
+        turn: usize,
+        direction: Direction,
+    },
+}
+
+pub struct ProcessSnakeController {
+    child: Child,
+    stdin: Option<ChildStdin>,
+    replies: Receiver<String>,
+    timeout: Duration,
+    name: String,
+    turn: usize,
+    snake_number: usize,
+    is_game_over: bool,
+}
+
+impl ProcessSnakeController {
+    pub fn spawn(
+        mut command: Command,
+        snake_number: usize,
+        timeout: Duration,
+    ) -> Result<Self, Error> {
+        let mut child = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+        let (stdin, stdout) = match (child.stdin.take(), child.stdout.take()) {
+            (Some(stdin), Some(stdout)) => (stdin, stdout),
+            _ => {
+                let _ = child.kill();
+                return Err(Error::Handshake);
+            }
+        };
+        let (sender, replies) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+        let mut controller = Self {
+            child,
+            stdin: Some(stdin),
+            replies,
+            timeout,
+            name: String::new(),
+            turn: 0,
+            snake_number,
+            is_game_over: false,
+        };
+        controller.send(&Message::Start {
+            protocol: PROTOCOL_VERSION,
+        })?;
+        match controller.receive(HANDSHAKE_TIMEOUT.max(timeout)) {
+            Some(Reply::Ready { name }) => {
+                controller.name = name;
+                Ok(controller)
+            }
+            _ => Err(Error::Handshake),
+        }
+    }
+    pub fn get_name(&self) -> &String {
+        &self.name
+    }
+    fn send(&mut self, message: &Message) -> Result<(), Error> {
+        let mut line = serde_json::to_string(message)?;
+        line.push('\n');
+        let stdin = self.stdin.as_mut().ok_or(Error::Handshake)?;
+        stdin.write_all(line.as_bytes())?;
+        stdin.flush()?;
+        Ok(())
+    }
+    fn receive(&mut self, timeout: Duration) -> Option<Reply> {
+        let line = self.replies.recv_timeout(timeout).ok()?;
+        serde_json::from_str(&line).ok()
+    }
+    fn receive_move(&mut self, turn: usize) -> Option<Direction> {
+        let deadline = Instant::now() + self.timeout;
+        loop {
+            let timeout = deadline.saturating_duration_since(Instant::now());
+            if let Some(Reply::Move {
+                turn: reply_turn,
+                direction,
+            }) = self.receive(timeout)
+            {
+                if reply_turn == turn {
+                    return Some(direction);
+                }
+            }
+            if Instant::now() >= deadline {
+                return None;
+            }
+        }
+    }
+    fn game_over(&mut self) {
+        if !self.is_game_over {
+            self.is_game_over = true;
+            let _ = self.send(&Message::GameOver);
+        }
+    }
+    fn wait_for_exit(&mut self) {
+        self.stdin = None;
+        let deadline = Instant::now() + self.timeout;
+        while self
+            .replies
+            .recv_timeout(deadline.saturating_duration_since(Instant::now()))
+            .is_ok()
+        {}
+        if let Ok(Some(_)) = self.child.try_wait() {
+            return;
+        }
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+    fn tick_message(&self, self_info: &SnakeInfo, world_view: &WorldView) -> Message {
+        let mut snakes: Vec<SnakeState> = world_view
+            .get_snakes_info()
+            .iter()
+            .map(|(number, snake_info)| SnakeState {
+                number: *number,
+                body: snake_info.get_snake().body_parts_points(true),
+                direction: *snake_info.get_direction(),
+            })
+            .collect();
+        snakes.sort_by_key(|snake| snake.number);
+        let you = snakes
+            .iter()
+            .find(|snake| snake.number == self.snake_number)
+            .cloned()
+            .unwrap_or(SnakeState {
+                number: self.snake_number,
+                body: self_info.get_snake().body_parts_points(true),
+                direction: *self_info.get_direction(),
+            });
+        let eat_points = world_view.get_eat_points();
+        Message::Tick {
+            turn: self.turn,
+            world_size: world_view.get_rules().world_size,
+            you,
+            snakes,
+            food: sorted_points(eat_points.keys())
+                .into_iter()
+                .map(|point| (point, eat_points[&point]))
+                .collect(),
+            borders: sorted_points(world_view.get_border_points()),
+        }
+    }
+}
+
+impl Drop for ProcessSnakeController {
+    fn drop(&mut self) {
+        self.game_over();
+        self.wait_for_exit();
+    }
+}
+
+impl SnakeController for ProcessSnakeController {
+    fn snake_will_burn(&mut self, _: &WorldView) {}
+    fn snake_did_burn(&mut self, _: &SnakeInfo, _: &WorldView) {
+        self.is_game_over = false;
+    }
+    fn snake_will_move(&mut self, self_info: &SnakeInfo, world_view: &WorldView) -> Direction {
+        let fallback_direction = self_info.get_direction().unwrap_or(Direction::Right);
+        let message = self.tick_message(self_info, world_view);
+        let turn = self.turn;
+        self.turn += 1;
+        if self.send(&message).is_err() {
+            return fallback_direction;
+        }
+        self.receive_move(turn).unwrap_or(fallback_direction)
+    }
+    fn snake_did_move(&mut self, _: &SnakeInfo, _: &WorldView) {}
+    fn snake_will_eat(&mut self, _: EatType, _: &SnakeInfo, _: &WorldView) {}
+    fn snake_did_eat(&mut self, _: EatType, _: &SnakeInfo, _: &WorldView) {}
+    fn snake_will_died(&mut self, _: &SnakeInfo, _: &WorldView) {}
+    fn snake_did_died(&mut self, world_view: &WorldView) {
+        let _ = self.send(&Message::Died);
+        let lives = world_view.get_snakes_lives().get(&self.snake_number);
+        if lives.is_none_or(|lives| *lives == 0) {
+            self.game_over();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::fixtures;
+    use super::super::super::world::World;
+    use super::*;
+
+    #[test]
+    fn stale_move_reply_is_discarded() {
+        let mut config = fixtures::config(12, 1);
+        config.snakes_controllers = fixtures::greedy_controllers(1);
+        let mut world = World::new(config).unwrap();
+        let world_view = world.tick(true);
+        let script = concat!(
+            r#"read line; echo '{"type":"ready","name":"script"}'; "#,
+            r#"read line; echo '{"type":"move","turn":7,"direction":"Up"}'; "#,
+            r#"echo '{"type":"move","turn":0,"direction":"Down"}'; "#,
+            "while read line; do :; done",
+        );
+        let mut command = Command::new("sh");
+        command.arg("-c").arg(script);
+        let mut controller =
+            ProcessSnakeController::spawn(command, 0, Duration::from_secs(5)).unwrap();
+        assert_eq!(controller.get_name(), "script");
+        let self_info = &world_view.get_snakes_info()[&0];
+        let direction = controller.snake_will_move(self_info, &world_view);
+        assert_eq!(direction, Direction::Down);
+    }
+
+    #[test]
+    fn tick_reports_the_assigned_snake_as_you() {
+        let mut config = fixtures::config(12, 1);
+        config.snakes_controllers = fixtures::greedy_controllers(2);
+        let mut world = World::new(config).unwrap();
+        let world_view = world.tick(true);
+        let script = concat!(
+            r#"read line; echo '{"type":"ready","name":"script"}'; "#,
+            "while read line; do :; done",
+        );
+        let mut command = Command::new("sh");
+        command.arg("-c").arg(script);
+        let controller = ProcessSnakeController::spawn(command, 1, Duration::from_secs(5)).unwrap();
+        let self_info = &world_view.get_snakes_info()[&1];
+        match controller.tick_message(self_info, &world_view) {
+            Message::Tick { you, .. } => {
+                assert_eq!(you.number, 1);
+                assert_eq!(you.body, self_info.get_snake().body_parts_points(true));
+            }
+            message => panic!("unexpected message {:?}", message),
+        }
+    }
+}
@@ -0,0 +1,569 @@
+use super::components::direction::Direction;
+use super::components::point::Point;
+use super::components::rng::SPLIT_MIX_RNG_KIND;
+use super::snapshot::{sorted_points, SnakeSnapshot, WorldSnapshot, SNAPSHOT_VERSION};
+use super::world::{Config, FoodKind, SnakeInfo, SnakeStats, Spawn, Topology, WorldView};
+use super::AreaSize;
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::time::Duration;
+
+pub const STANDARD_RULESET: &str = "standard";
+pub const WRAPPED_RULESET: &str = "wrapped";
+pub const DEFAULT_HEALTH: u32 = 100;
+
+#[derive(Debug)]
+pub enum Error {
+    Json(serde_json::Error),
+    BoardSize,
+    OutOfBoard(Coord),
+    BrokenBody(String),
+    SpawnsLack,
+    UnknownSnake,
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(err: serde_json::Error) -> Self {
+        Self::Json(err)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Coord {
+    pub x: i32,
+    pub y: i32,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Ruleset {
+    pub name: String,
+    #[serde(default)]
+    pub version: String,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Game {
+    pub id: String,
+    #[serde(default)]
+    pub ruleset: Ruleset,
+    #[serde(default)]
+    pub map: String,
+    #[serde(default)]
+    pub timeout: u64,
+    #[serde(default)]
+    pub source: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Battlesnake {
+    pub id: String,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default = "default_health")]
+    pub health: u32,
+    pub body: Vec<Coord>,
+    #[serde(default)]
+    pub latency: String,
+    pub head: Coord,
+    pub length: usize,
+    #[serde(default)]
+    pub shout: String,
+    #[serde(default)]
+    pub squad: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Board {
+    pub height: i32,
+    pub width: i32,
+    pub food: Vec<Coord>,
+    #[serde(default)]
+    pub hazards: Vec<Coord>,
+    pub snakes: Vec<Battlesnake>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameState {
+    pub game: Game,
+    pub turn: usize,
+    pub board: Board,
+    pub you: Battlesnake,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MoveResponse {
+    #[serde(rename = "move")]
+    pub direction: String,
+    #[serde(default)]
+    pub shout: String,
+}
+
+pub fn default_health() -> u32 {
+    DEFAULT_HEALTH
+}
+
+pub fn direction_name(direction: Direction) -> &'static str {
+    match direction {
+        Direction::Right => "right",
+        Direction::Left => "left",
+        Direction::Down => "down",
+        Direction::Up => "up",
+    }
+}
+
+pub fn parse_direction(name: &str) -> Option<Direction> {
+    match name.trim().to_ascii_lowercase().as_str() {
+        "right" => Some(Direction::Right),
+        "left" => Some(Direction::Left),
+        "down" => Some(Direction::Down),
+        "up" => Some(Direction::Up),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub struct BoardMapping {
+    offset: AreaSize,
+    width: AreaSize,
+    height: AreaSize,
+}
+
+impl BoardMapping {
+    pub fn new(world_size: (AreaSize, AreaSize), topology: Topology) -> Self {
+        match topology {
+            Topology::Bounded => Self {
+                offset: 1,
+                width: world_size.0.saturating_sub(2),
+                height: world_size.1.saturating_sub(2),
+            },
+            Topology::Torus => Self {
+                offset: 0,
+                width: world_size.0,
+                height: world_size.1,
+            },
+        }
+    }
+    pub fn get_width(&self) -> AreaSize {
+        self.width
+    }
+    pub fn get_height(&self) -> AreaSize {
+        self.height
+    }
+    pub fn to_coord(&self, point: &Point<AreaSize>) -> Coord {
+        let x = point.x() as i32 - self.offset as i32;
+        let y = point.y() as i32 - self.offset as i32;
+        Coord {
+            x,
+            y: self.height as i32 - 1 - y,
+        }
+    }
+    pub fn to_point(&self, coord: &Coord) -> Option<Point<AreaSize>> {
+        if coord.x < 0 || coord.y < 0 {
+            return None;
+        }
+        if coord.x >= self.width as i32 || coord.y >= self.height as i32 {
+            return None;
+        }
+        let x = coord.x as AreaSize + self.offset;
+        let y = (self.height as i32 - 1 - coord.y) as AreaSize + self.offset;
+        Some(Point::new(x, y))
+    }
+    pub fn to_battlesnake(
+        &self,
+        snake_number: usize,
+        body_parts_points: &[Point<AreaSize>],
+        stomach_size: usize,
+    ) -> Option<Battlesnake> {
+        let mut body: Vec<Coord> = body_parts_points
+            .iter()
+            .rev()
+            .map(|point| self.to_coord(point))
+            .collect();
+        let tail = *body.last()?;
+        body.extend(std::iter::repeat_n(tail, stomach_size));
+        Some(Battlesnake {
+            id: snake_number.to_string(),
+            name: format!("snake-{}", snake_number),
+            health: DEFAULT_HEALTH,
+            head: *body.first()?,
+            length: body.len(),
+            body,
+            latency: String::new(),
+            shout: String::new(),
+            squad: String::new(),
+        })
+    }
+    pub fn to_board(
+        &self,
+        snakes: Vec<Battlesnake>,
+        eat_points: &[(Point<AreaSize>, FoodKind)],
+        border_points: &[Point<AreaSize>],
+    ) -> Board {
+        let food = eat_points
+            .iter()
+            .filter(|(_, food_kind)| *food_kind != FoodKind::Poison)
+            .map(|(point, _)| self.to_coord(point))
+            .collect();
+        let hazards = border_points
+            .iter()
+            .map(|point| self.to_coord(point))
+            .filter(|coord| self.to_point(coord).is_some())
+            .collect();
+        Board {
+            height: self.height as i32,
+            width: self.width as i32,
+            food,
+            hazards,
+            snakes,
+        }
+    }
+    fn snake_snapshot(
+        &self,
+        battlesnake: &Battlesnake,
+        wrap_size: Option<(AreaSize, AreaSize)>,
+    ) -> Result<SnakeSnapshot, Error> {
+        let mut body_parts_points: Vec<Point<AreaSize>> = Vec::new();
+        let mut stomach_size = 0;
+        for coord in &battlesnake.body {
+            let point = self.to_point(coord).ok_or(Error::OutOfBoard(*coord))?;
+            match body_parts_points.last() {
+                Some(last_point) if *last_point == point => stomach_size += 1,
+                Some(_) if stomach_size > 0 => {
+                    return Err(Error::BrokenBody(battlesnake.id.clone()));
+                }
+                Some(last_point) if direction_between(last_point, &point, wrap_size).is_none() => {
+                    return Err(Error::BrokenBody(battlesnake.id.clone()));
+                }
+                _ => body_parts_points.push(point),
+            }
+        }
+        let direction = match body_parts_points.as_slice() {
+            [head_point, neck_point, ..] => direction_between(neck_point, head_point, wrap_size),
+            [_] => None,
+            [] => return Err(Error::BrokenBody(battlesnake.id.clone())),
+        };
+        body_parts_points.reverse();
+        Ok(SnakeSnapshot {
+            body_parts_points,
+            direction,
+            stomach_size,
+            speed_effect: None,
+        })
+    }
+}
+
+fn direction_between(
+    from: &Point<AreaSize>,
+    to: &Point<AreaSize>,
+    wrap_size: Option<(AreaSize, AreaSize)>,
+) -> Option<Direction> {
+    Direction::ALL
+        .into_iter()
+        .find(|direction| from.moved_to(*direction, wrap_size) == *to)
+}
+
+fn free_spawns(config: &Config, occupied_points: &HashSet<Point<AreaSize>>) -> Vec<Spawn> {
+    let border_points = config.border_points();
+    let spawn_length = config.rules.base_snake_tail_size as AreaSize + 1;
+    let mut spawns = Vec::new();
+    for y in (1..config.rules.world_size.1).step_by(2) {
+        let mut x = 0;
+        while x + spawn_length <= config.rules.world_size.0 {
+            let spawn = Spawn {
+                point: Point::new(x, y),
+                direction: Direction::Right,
+            };
+            let is_free = config.spawn_body_points(&spawn).is_some_and(|body_points| {
+                body_points
+                    .iter()
+                    .all(|point| !border_points.contains(point) && !occupied_points.contains(point))
+            });
+            if is_free {
+                spawns.push(spawn);
+                x += spawn_length + 1;
+            } else {
+                x += 1;
+            }
+        }
+    }
+    spawns
+}
+
+impl Board {
+    pub fn from_snapshot(snapshot: &WorldSnapshot) -> Self {
+        let config = &snapshot.config;
+        let mapping = BoardMapping::new(config.rules.world_size, config.rules.topology);
+        let snakes = snapshot
+            .snakes
+            .iter()
+            .filter_map(|(snake_number, snake_snapshot)| {
+                mapping.to_battlesnake(
+                    *snake_number,
+                    &snake_snapshot.body_parts_points,
+                    snake_snapshot.stomach_size,
+                )
+            })
+            .collect();
+        mapping.to_board(snakes, &snapshot.eat_points, &snapshot.border_points)
+    }
+    pub fn to_snapshot(&self, mut config: Config, turn: usize) -> Result<WorldSnapshot, Error> {
+        let width = AreaSize::try_from(self.width).map_err(|_| Error::BoardSize)?;
+        let height = AreaSize::try_from(self.height).map_err(|_| Error::BoardSize)?;
+        config.rules.world_size = match config.rules.topology {
+            Topology::Bounded => (
+                width.checked_add(2).ok_or(Error::BoardSize)?,
+                height.checked_add(2).ok_or(Error::BoardSize)?,
+            ),
+            Topology::Torus => (width, height),
+        };
+        let mapping = BoardMapping::new(config.rules.world_size, config.rules.topology);
+        let wrap_size = config.rules.wrap_size();
+        let mut walls = Vec::new();
+        for coord in &self.hazards {
+            walls.push(mapping.to_point(coord).ok_or(Error::OutOfBoard(*coord))?);
+        }
+        config.walls = sorted_points(&walls);
+        config.walls.dedup();
+        let mut eat_points = Vec::new();
+        for coord in &self.food {
+            let point = mapping.to_point(coord).ok_or(Error::OutOfBoard(*coord))?;
+            eat_points.push((point, FoodKind::Normal));
+        }
+        eat_points.sort_by_key(|(point, _)| (point.y(), point.x()));
+        eat_points.dedup();
+        let food_count = AreaSize::try_from(eat_points.len()).map_err(|_| Error::BoardSize)?;
+        config.rules.eat_count = config.rules.eat_count.max(food_count);
+        let ids: Vec<Option<usize>> = self
+            .snakes
+            .iter()
+            .map(|battlesnake| battlesnake.id.parse().ok())
+            .collect();
+        let unique_ids: HashSet<&Option<usize>> = ids.iter().collect();
+        let use_ids = unique_ids.len() == ids.len() && ids.iter().all(Option::is_some);
+        let mut snakes = BTreeMap::new();
+        for (index, battlesnake) in self.snakes.iter().enumerate() {
+            let snake_number = match ids[index] {
+                Some(id) if use_ids => id,
+                _ => index,
+            };
+            snakes.insert(
+                snake_number,
+                mapping.snake_snapshot(battlesnake, wrap_size)?,
+            );
+        }
+        let occupied_points: HashSet<Point<AreaSize>> = snakes
+            .values()
+            .flat_map(|snake_snapshot| snake_snapshot.body_parts_points.iter().copied())
+            .collect();
+        config.food_spawners = Vec::new();
+        config.spawns = free_spawns(&config, &occupied_points);
+        if config.spawns.len() < snakes.len().max(1) {
+            return Err(Error::SpawnsLack);
+        }
+        let border_points = sorted_points(&config.border_points());
+        let snakes_numbers: Vec<usize> = snakes.keys().copied().collect();
+        Ok(WorldSnapshot {
+            version: SNAPSHOT_VERSION,
+            rng_kind: SPLIT_MIX_RNG_KIND.to_string(),
+            rng_state: config.seed,
+            tick_number: turn,
+            snakes,
+            snakes_stats: snakes_numbers
+                .iter()
+                .map(|snake_number| (*snake_number, SnakeStats::default()))
+                .collect(),
+            snakes_lives: snakes_numbers
+                .iter()
+                .map(|snake_number| (*snake_number, config.rules.lives))
+                .collect(),
+            snakes_respawns: BTreeMap::new(),
+            match_end: None,
+            eat_points,
+            border_points,
+            config,
+        })
+    }
+}
+
+impl GameState {
+    pub fn from_world_view(
+        world_view: &WorldView,
+        self_info: &SnakeInfo,
+        game_id: &str,
+        timeout: Duration,
+    ) -> Result<Self, Error> {
+        let rules = world_view.get_rules();
+        let mapping = BoardMapping::new(rules.world_size, rules.topology);
+        let mut snakes_numbers: Vec<&usize> = world_view.get_snakes_info().keys().collect();
+        snakes_numbers.sort();
+        let to_battlesnake = |snake_number: usize, snake_info: &SnakeInfo| {
+            let snake = snake_info.get_snake();
+            mapping.to_battlesnake(
+                snake_number,
+                &snake.body_parts_points(true),
+                snake.stomach_size(),
+            )
+        };
+        let snakes: Vec<Battlesnake> = snakes_numbers
+            .into_iter()
+            .filter_map(|snake_number| {
+                let snake_info = world_view.get_snakes_info().get(snake_number)?;
+                to_battlesnake(*snake_number, snake_info)
+            })
+            .collect();
+        let self_head = mapping.to_coord(&self_info.get_snake().head_point());
+        let you = snakes
+            .iter()
+            .find(|snake| snake.head == self_head)
+            .cloned()
+            .ok_or(Error::UnknownSnake)?;
+        let eat_points = world_view.get_eat_points();
+        let eat_points: Vec<(Point<AreaSize>, FoodKind)> = sorted_points(eat_points.keys())
+            .into_iter()
+            .map(|point| (point, eat_points[&point]))
+            .collect();
+        let border_points = sorted_points(world_view.get_border_points());
+        Ok(Self {
+            game: Self::game(rules.topology, game_id, timeout),
+            turn: world_view.get_tick_number(),
+            board: mapping.to_board(snakes, &eat_points, &border_points),
+            you,
+        })
+    }
+    pub fn from_snapshot(
+        snapshot: &WorldSnapshot,
+        snake_number: usize,
+        game_id: &str,
+        timeout: Duration,
+    ) -> Option<Self> {
+        let board = Board::from_snapshot(snapshot);
+        let id = snake_number.to_string();
+        let you = board.snakes.iter().find(|snake| snake.id == id)?.clone();
+        Some(Self {
+            game: Self::game(snapshot.config.rules.topology, game_id, timeout),
+            turn: snapshot.tick_number,
+            board,
+            you,
+        })
+    }
+    pub fn to_snapshot(&self, mut config: Config) -> Result<WorldSnapshot, Error> {
+        config.rules.topology = match self.game.ruleset.name.as_str() {
+            WRAPPED_RULESET => Topology::Torus,
+            _ => Topology::Bounded,
+        };
+        self.board.to_snapshot(config, self.turn)
+    }
+    pub fn to_json(&self) -> Result<String, Error> {
+        Ok(serde_json::to_string(self)?)
+    }
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        Ok(serde_json::from_str(json)?)
+    }
+    fn game(topology: Topology, game_id: &str, timeout: Duration) -> Game {
+        let ruleset_name = match topology {
+            Topology::Bounded => STANDARD_RULESET,
+            Topology::Torus => WRAPPED_RULESET,
+        };
+        Game {
+            id: game_id.to_string(),
+            ruleset: Ruleset {
+                name: ruleset_name.to_string(),
+                version: String::new(),
+            },
+            map: String::new(),
+            timeout: timeout.as_millis() as u64,
+            source: String::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::fixtures::{self, greedy_controllers};
+    use super::super::world::World;
+    use super::*;
+
+    #[test]
+    fn standard_small_board_imports() {
+        let state_json = r#"{
+            "game": {"id": "small", "ruleset": {"name": "standard"}},
+            "turn": 12,
+            "board": {
+                "height": 7,
+                "width": 7,
+                "food": [{"x": 3, "y": 3}],
+                "snakes": [
+                    {"id": "0", "body": [{"x": 1, "y": 5}, {"x": 1, "y": 4}, {"x": 1, "y": 3}], "head": {"x": 1, "y": 5}, "length": 3},
+                    {"id": "1", "body": [{"x": 5, "y": 1}, {"x": 5, "y": 2}, {"x": 5, "y": 3}], "head": {"x": 5, "y": 1}, "length": 3}
+                ]
+            },
+            "you": {"id": "0", "body": [{"x": 1, "y": 5}, {"x": 1, "y": 4}, {"x": 1, "y": 3}], "head": {"x": 1, "y": 5}, "length": 3}
+        }"#;
+        let state = GameState::from_json(state_json).unwrap();
+        let snapshot = state.to_snapshot(fixtures::config(10, 1)).unwrap();
+        assert_eq!(snapshot.config.rules.world_size, (9, 9));
+        let world = World::from_snapshot(snapshot, greedy_controllers(2)).unwrap();
+        let board = Board::from_snapshot(&world.snapshot());
+        assert_eq!(board.width, 7);
+        assert_eq!(board.food, state.board.food);
+        for (snake, imported_snake) in state.board.snakes.iter().zip(&board.snakes) {
+            assert_eq!(snake.id, imported_snake.id);
+            assert_eq!(snake.body, imported_snake.body);
+        }
+    }
+
+    #[test]
+    fn imported_food_raises_eat_count() {
+        let state_json = r#"{
+            "game": {"id": "food", "ruleset": {"name": "standard"}},
+            "turn": 3,
+            "board": {
+                "height": 7,
+                "width": 7,
+                "food": [{"x": 3, "y": 3}, {"x": 0, "y": 0}, {"x": 6, "y": 6}],
+                "snakes": [
+                    {"id": "0", "body": [{"x": 1, "y": 5}, {"x": 1, "y": 4}, {"x": 1, "y": 3}], "head": {"x": 1, "y": 5}, "length": 3}
+                ]
+            },
+            "you": {"id": "0", "body": [{"x": 1, "y": 5}, {"x": 1, "y": 4}, {"x": 1, "y": 3}], "head": {"x": 1, "y": 5}, "length": 3}
+        }"#;
+        let state = GameState::from_json(state_json).unwrap();
+        let snapshot = state.to_snapshot(fixtures::config(10, 1)).unwrap();
+        assert_eq!(snapshot.config.rules.eat_count, 3);
+        let mut world = World::from_snapshot(snapshot, greedy_controllers(1)).unwrap();
+        world.tick(false);
+        assert!(world.snapshot().eat_points.len() <= 3);
+    }
+
+    #[test]
+    fn poison_is_not_exported_as_hazard() {
+        let mapping = BoardMapping::new((10, 10), Topology::Bounded);
+        let eat_points = [
+            (Point::new(2, 2), FoodKind::Normal),
+            (Point::new(3, 3), FoodKind::Poison),
+        ];
+        let board = mapping.to_board(Vec::new(), &eat_points, &[]);
+        assert_eq!(board.food, vec![mapping.to_coord(&Point::new(2, 2))]);
+        assert!(board.hazards.is_empty());
+    }
+
+    #[test]
+    fn missing_you_is_reported() {
+        let mut config = fixtures::config(12, 1);
+        config.snakes_controllers = greedy_controllers(1);
+        let mut world = World::new(config).unwrap();
+        let mut other_config = fixtures::config(12, 1);
+        other_config.snakes_controllers = greedy_controllers(1);
+        other_config.spawns = vec![Spawn {
+            point: Point::new(2, 9),
+            direction: Direction::Right,
+        }];
+        let mut other_world = World::new(other_config).unwrap();
+        let other_world_view = other_world.tick(true);
+        let other_info = &other_world_view.get_snakes_info()[&0];
+        let world_view = world.tick(true);
+        let state = GameState::from_world_view(&world_view, other_info, "game", Duration::ZERO);
+        assert!(matches!(state, Err(Error::UnknownSnake)));
+    }
+}
@@ -0,0 +1,295 @@
+use super::components::direction::Direction;
+use super::components::point::Point;
+use super::world::{
+    Config, CreateError, EatType, Event, FoodKind, MatchEnd, ObjectType, SnakeController,
+    SnakeInfo, SnakeStats, World, WorldView,
+};
+use super::AreaSize;
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+pub const HEAD_VALUE: f32 = 1.0;
+pub const BODY_VALUE: f32 = 0.5;
+pub const CELL_VALUE: f32 = 1.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RewardConfig {
+    pub food: f32,
+    pub survival: f32,
+    pub kill: f32,
+    pub death: f32,
+}
+
+impl Default for RewardConfig {
+    fn default() -> Self {
+        Self {
+            food: 1.0,
+            survival: 0.01,
+            kill: 1.0,
+            death: -1.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Observation {
+    pub channels: usize,
+    pub height: usize,
+    pub width: usize,
+    pub data: Vec<f32>,
+}
+
+impl Observation {
+    fn new(layers: &[ObjectType], world_view: &WorldView) -> Self {
+        let (width, height) = world_view.get_rules().world_size;
+        let (width, height) = (width as usize, height as usize);
+        let mut observation = Self {
+            channels: layers.len(),
+            height,
+            width,
+            data: vec![0.0; layers.len() * height * width],
+        };
+        observation.fill(layers, world_view);
+        observation
+    }
+    fn fill(&mut self, layers: &[ObjectType], world_view: &WorldView) {
+        self.data.fill(0.0);
+        let world_mask = world_view.get_world_mask();
+        for (channel, layer_key) in layers.iter().enumerate() {
+            let value = match layer_key {
+                ObjectType::Snake(_) => BODY_VALUE,
+                _ => CELL_VALUE,
+            };
+            for point in world_mask.get_layer(layer_key).into_iter().flatten() {
+                self.set(channel, point, value);
+            }
+            if let ObjectType::Snake(snake_number) = layer_key {
+                if let Some(snake_info) = world_view.get_snakes_info().get(snake_number) {
+                    let head_point = snake_info.get_snake().head_point();
+                    self.set(channel, &head_point, HEAD_VALUE);
+                }
+            }
+        }
+    }
+    pub fn get_shape(&self) -> (usize, usize, usize) {
+        (self.channels, self.height, self.width)
+    }
+    pub fn get_channel(&self, channel: usize) -> &[f32] {
+        let size = self.height * self.width;
+        &self.data[channel * size..(channel + 1) * size]
+    }
+    pub fn get(&self, channel: usize, point: &Point<AreaSize>) -> f32 {
+        self.index(channel, point)
+            .map(|index| self.data[index])
+            .unwrap_or(0.0)
+    }
+    fn set(&mut self, channel: usize, point: &Point<AreaSize>, value: f32) {
+        if let Some(index) = self.index(channel, point) {
+            self.data[index] = value;
+        }
+    }
+    fn index(&self, channel: usize, point: &Point<AreaSize>) -> Option<usize> {
+        let (x, y) = (point.x() as usize, point.y() as usize);
+        if channel >= self.channels || x >= self.width || y >= self.height {
+            return None;
+        }
+        Some((channel * self.height + y) * self.width + x)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StepInfo {
+    pub tick_number: usize,
+    pub alive: Vec<bool>,
+    pub events: Vec<Event>,
+    pub match_end: Option<MatchEnd>,
+}
+
+#[derive(Debug, Default)]
+struct ActionSnakeController {
+    action: Option<Direction>,
+}
+
+impl SnakeController for ActionSnakeController {
+    fn snake_will_burn(&mut self, _: &WorldView) {}
+    fn snake_did_burn(&mut self, _: &SnakeInfo, _: &WorldView) {}
+    fn snake_will_move(&mut self, self_info: &SnakeInfo, _: &WorldView) -> Direction {
+        match (self.action, self_info.get_direction()) {
+            (Some(direction), _) => direction,
+            (None, Some(direction)) => *direction,
+            (None, None) => Direction::Right,
+        }
+    }
+    fn snake_did_move(&mut self, _: &SnakeInfo, _: &WorldView) {}
+    fn snake_will_eat(&mut self, _: EatType, _: &SnakeInfo, _: &WorldView) {}
+    fn snake_did_eat(&mut self, _: EatType, _: &SnakeInfo, _: &WorldView) {}
+    fn snake_will_died(&mut self, _: &SnakeInfo, _: &WorldView) {}
+    fn snake_did_died(&mut self, _: &WorldView) {}
+}
+
+pub struct Environment {
+    config: Config,
+    reward_config: RewardConfig,
+    agents: Vec<usize>,
+    layers: Vec<ObjectType>,
+    controllers: Vec<Arc<Mutex<ActionSnakeController>>>,
+    previous_stats: HashMap<usize, SnakeStats>,
+    world: World,
+    observation: Observation,
+    done: bool,
+}
+
+impl Environment {
+    pub fn new(
+        mut config: Config,
+        agents_count: usize,
+        reward_config: RewardConfig,
+    ) -> Result<Self, CreateError> {
+        let agents: Vec<usize> = (0..agents_count).collect();
+        let controllers: Vec<Arc<Mutex<ActionSnakeController>>> = agents
+            .iter()
+            .map(|_| Arc::new(Mutex::new(ActionSnakeController::default())))
+            .collect();
+        config.snakes_controllers = HashMap::new();
+        for (snake_number, controller) in agents.iter().zip(&controllers) {
+            config
+                .snakes_controllers
+                .insert(*snake_number, controller.clone());
+        }
+        config.rules.move_deadline = None;
+        let mut layers = vec![ObjectType::Border];
+        layers.extend(
+            agents
+                .iter()
+                .map(|snake_number| ObjectType::Snake(*snake_number)),
+        );
+        layers.extend(
+            FoodKind::ALL
+                .iter()
+                .map(|food_kind| ObjectType::Eat(*food_kind)),
+        );
+        let mut world = World::new(config.clone())?;
+        let observation = Observation::new(&layers, &world.tick(true));
+        Ok(Self {
+            previous_stats: world.get_snakes_stats().clone(),
+            config,
+            reward_config,
+            agents,
+            layers,
+            controllers,
+            world,
+            observation,
+            done: false,
+        })
+    }
+    pub fn reset(&mut self, seed: u64) -> Result<&Observation, CreateError> {
+        let mut config = self.config.clone();
+        config.seed = seed;
+        self.world = World::new(config)?;
+        self.set_actions(&[]);
+        self.observation.fill(&self.layers, &self.world.tick(true));
+        self.previous_stats = self.world.get_snakes_stats().clone();
+        self.done = self.world.is_match_over();
+        Ok(&self.observation)
+    }
+    pub fn step(&mut self, actions: &[Direction]) -> (&Observation, Vec<f32>, bool, StepInfo) {
+        if self.done {
+            let info = self.info(&vec![false; self.agents.len()], Vec::new());
+            let rewards = vec![0.0; self.agents.len()];
+            return (&self.observation, rewards, true, info);
+        }
+        self.set_actions(actions);
+        let world_view = self.world.tick(false);
+        let alive: Vec<bool> = self
+            .agents
+            .iter()
+            .map(|snake_number| world_view.get_snakes_info().contains_key(snake_number))
+            .collect();
+        let rewards: Vec<f32> = self
+            .agents
+            .iter()
+            .zip(&alive)
+            .map(|(snake_number, is_alive)| {
+                let snake_stats = world_view.get_snakes_stats().get(snake_number);
+                let previous_stats = self.previous_stats.get(snake_number);
+                let snake_stats = snake_stats.copied().unwrap_or_default();
+                let previous_stats = previous_stats.copied().unwrap_or_default();
+                let delta = |value: fn(&SnakeStats) -> usize| {
+                    value(&snake_stats).saturating_sub(value(&previous_stats)) as f32
+                };
+                let survival = if *is_alive { 1.0 } else { 0.0 };
+                self.reward_config.food * delta(|stats| stats.food_eaten)
+                    + self.reward_config.kill * delta(|stats| stats.kills)
+                    + self.reward_config.death * delta(|stats| stats.deaths)
+                    + self.reward_config.survival * survival
+            })
+            .collect();
+        self.observation.fill(&self.layers, &world_view);
+        let events = world_view.get_events().clone();
+        self.previous_stats = world_view.get_snakes_stats().clone();
+        self.done = world_view.get_match_end().is_some();
+        let info = self.info(&alive, events);
+        (&self.observation, rewards, self.done, info)
+    }
+    pub fn get_observation(&self) -> &Observation {
+        &self.observation
+    }
+    pub fn get_observation_shape(&self) -> (usize, usize, usize) {
+        self.observation.get_shape()
+    }
+    pub fn get_layers(&self) -> &Vec<ObjectType> {
+        &self.layers
+    }
+    pub fn get_agents(&self) -> &Vec<usize> {
+        &self.agents
+    }
+    pub fn get_world(&self) -> &World {
+        &self.world
+    }
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+    fn set_actions(&mut self, actions: &[Direction]) {
+        for (index, controller) in self.controllers.iter().enumerate() {
+            if let Ok(mut controller) = controller.lock() {
+                controller.action = actions.get(index).copied();
+            }
+        }
+    }
+    fn info(&self, alive: &[bool], events: Vec<Event>) -> StepInfo {
+        StepInfo {
+            tick_number: self.world.get_tick_number(),
+            alive: alive.to_vec(),
+            events,
+            match_end: *self.world.get_match_end(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::fixtures;
+    use super::*;
+
+    #[test]
+    fn steps_reuse_observation_without_replay() {
+        let mut config = fixtures::config(12, 1);
+        config.record_replay = false;
+        let mut environment = Environment::new(config, 1, RewardConfig::default()).unwrap();
+        let data_pointer = environment.reset(2).unwrap().data.as_ptr();
+        for _ in 0..5 {
+            let (observation, _, _, _) = environment.step(&[Direction::Down]);
+            assert_eq!(observation.data.as_ptr(), data_pointer);
+        }
+        let world = environment.get_world();
+        assert!(world.get_replay().get_ticks().is_empty());
+        let snapshot = world.snapshot();
+        let head_point = *snapshot.snakes[&0].body_parts_points.last().unwrap();
+        assert_eq!(
+            environment.get_observation().get(1, &head_point),
+            HEAD_VALUE
+        );
+    }
+}
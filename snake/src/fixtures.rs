@@ -0,0 +1,22 @@
+use super::ai::greedy::GreedySnakeController;
+use super::world::{Config, SnakeController};
+use super::AreaSize;
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+pub fn config(world_size: AreaSize, seed: u64) -> Config {
+    let config_json = format!(
+        r#"{{"rules":{{"world_size":[{world_size},{world_size}],"eat_count":1,"base_snake_tail_size":2}},"seed":{seed}}}"#
+    );
+    serde_json::from_str(&config_json).unwrap()
+}
+
+pub fn greedy_controllers(count: usize) -> HashMap<usize, Arc<Mutex<dyn SnakeController>>> {
+    let mut snakes_controllers: HashMap<usize, Arc<Mutex<dyn SnakeController>>> = HashMap::new();
+    for snake_number in 0..count {
+        let controller = Arc::new(Mutex::new(GreedySnakeController::new()));
+        snakes_controllers.insert(snake_number, controller);
+    }
+    snakes_controllers
+}
@@ -1,19 +1,17 @@
+use super::replay::Replay;
 use super::world;
 
-use std::cell::{RefCell, RefMut};
+use std::collections::BTreeMap;
 use std::hash::Hash;
-use std::rc::Rc;
+use std::sync::{Arc, Mutex, MutexGuard};
 
 pub struct Config {
-    pub game_controller: Rc<RefCell<dyn GameController>>,
+    pub game_controller: Arc<Mutex<dyn GameController>>,
 }
 
 impl Config {
-    fn game_controller(&self) -> Option<RefMut<dyn GameController>> {
-        match self.game_controller.try_borrow_mut() {
-            Ok(v) => Some(v),
-            Err(_) => None,
-        }
+    fn game_controller(&self) -> Option<MutexGuard<'_, dyn GameController + 'static>> {
+        self.game_controller.lock().ok()
     }
 }
 
@@ -33,12 +31,54 @@ pub enum TickType {
     Break,
 }
 
-pub trait GameController {
+#[derive(Debug, Clone)]
+pub struct GameResult {
+    pub replay: Replay,
+    pub ticks: usize,
+    pub snakes_stats: BTreeMap<usize, world::SnakeStats>,
+    pub match_result: world::MatchResult,
+}
+
+impl GameResult {
+    fn new(world: &world::World) -> Self {
+        let snakes_stats = world.get_snakes_stats().clone();
+        Self {
+            replay: world.get_replay().clone(),
+            ticks: world.get_tick_number(),
+            snakes_stats: BTreeMap::from_iter(snakes_stats),
+            match_result: world.match_result(),
+        }
+    }
+}
+
+pub trait GameController: Send {
     fn game_action(&mut self) -> ActionType;
     fn game_start(&mut self) -> world::Config;
     fn game_will_tick(&mut self, previous_world_view: &Option<world::WorldView>) -> TickType;
     fn game_did_tick(&mut self, world_view: &world::WorldView);
-    fn game_end(&mut self, state: Result<(), world::CreateError>);
+    fn game_end(&mut self, state: Result<GameResult, world::CreateError>);
+}
+
+pub struct Match {
+    world: world::World,
+    started: bool,
+}
+
+impl Match {
+    pub fn step(&mut self) -> world::WorldView<'_> {
+        let reset = !self.started;
+        self.started = true;
+        self.world.tick(reset)
+    }
+    pub fn is_over(&self) -> bool {
+        self.world.is_match_over()
+    }
+    pub fn result(&self) -> GameResult {
+        GameResult::new(&self.world)
+    }
+    pub fn get_world(&self) -> &world::World {
+        &self.world
+    }
 }
 
 pub struct Game {
@@ -49,6 +89,12 @@ impl Game {
     pub fn new(config: Config) -> Result<Self, CreateError> {
         Ok(Self { config })
     }
+    pub fn new_match(config: world::Config) -> Result<Match, world::CreateError> {
+        Ok(Match {
+            world: world::World::new(config)?,
+            started: false,
+        })
+    }
     pub fn start(&mut self) {
         self.start_game_loop();
     }
@@ -67,7 +113,7 @@ impl Game {
                     let start_result = match world::World::new(world_config) {
                         Ok(mut world) => {
                             self.start_tick_loop(&mut world);
-                            Ok(())
+                            Ok(GameResult::new(&world))
                         }
                         Err(err) => Err(err),
                     };
@@ -100,3 +146,29 @@ impl Game {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::super::fixtures;
+    use super::*;
+
+    fn match_config(seed: u64) -> world::Config {
+        let mut config = fixtures::config(20, seed);
+        config.rules.eat_count = 3;
+        config.rules.base_snake_tail_size = 3;
+        config.snakes_controllers = fixtures::greedy_controllers(2);
+        config
+    }
+
+    #[test]
+    fn step_returns_tick_events() {
+        let mut game_match = Game::new_match(match_config(1)).unwrap();
+        let world_view = game_match.step();
+        let spawned_count = world_view
+            .get_events()
+            .iter()
+            .filter(|event| matches!(event, world::Event::Spawned { .. }))
+            .count();
+        assert_eq!(spawned_count, 2);
+    }
+}
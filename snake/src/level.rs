@@ -0,0 +1,170 @@
+use super::components::direction::Direction;
+use super::components::point::Point;
+use super::world::{Config, Spawn};
+use super::AreaSize;
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+pub const WALL_SYMBOL: char = '#';
+pub const EMPTY_SYMBOLS: [char; 2] = ['.', ' '];
+pub const FOOD_SPAWNER_SYMBOL: char = '@';
+/// A spawn symbol marks the tail cell of a snake; its body extends from there
+/// in the facing direction, so the head starts `base_snake_tail_size` cells away.
+pub const SPAWN_RIGHT_SYMBOL: char = '>';
+pub const SPAWN_LEFT_SYMBOL: char = '<';
+pub const SPAWN_DOWN_SYMBOL: char = 'v';
+pub const SPAWN_UP_SYMBOL: char = '^';
+pub const COMMENT_PREFIX: &str = ";";
+
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    Empty,
+    TooLarge,
+    UnknownSymbol(char, Point<AreaSize>),
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Level {
+    world_size: (AreaSize, AreaSize),
+    walls: Vec<Point<AreaSize>>,
+    spawns: Vec<Spawn>,
+    food_spawners: Vec<Point<AreaSize>>,
+}
+
+impl Level {
+    pub fn parse(text: &str) -> Result<Self, Error> {
+        let rows: Vec<&str> = text
+            .lines()
+            .filter(|line| !line.starts_with(COMMENT_PREFIX))
+            .collect();
+        let rows = match rows.iter().rposition(|row| !row.trim().is_empty()) {
+            Some(last_row) => &rows[..=last_row],
+            None => return Err(Error::Empty),
+        };
+        let width = rows
+            .iter()
+            .map(|row| row.chars().count())
+            .max()
+            .unwrap_or(0);
+        let height = rows.len();
+        if width > AreaSize::MAX as usize || height > AreaSize::MAX as usize {
+            return Err(Error::TooLarge);
+        }
+        let mut level = Self {
+            world_size: (width as AreaSize, height as AreaSize),
+            walls: Vec::new(),
+            spawns: Vec::new(),
+            food_spawners: Vec::new(),
+        };
+        for (y, row) in rows.iter().enumerate() {
+            for (x, symbol) in row.chars().enumerate() {
+                let point = Point::new(x as AreaSize, y as AreaSize);
+                let spawn_direction = match symbol {
+                    SPAWN_RIGHT_SYMBOL => Some(Direction::Right),
+                    SPAWN_LEFT_SYMBOL => Some(Direction::Left),
+                    SPAWN_DOWN_SYMBOL => Some(Direction::Down),
+                    SPAWN_UP_SYMBOL => Some(Direction::Up),
+                    _ => None,
+                };
+                if let Some(direction) = spawn_direction {
+                    level.spawns.push(Spawn { point, direction });
+                } else if symbol == WALL_SYMBOL {
+                    level.walls.push(point);
+                } else if symbol == FOOD_SPAWNER_SYMBOL {
+                    level.food_spawners.push(point);
+                } else if !EMPTY_SYMBOLS.contains(&symbol) {
+                    return Err(Error::UnknownSymbol(symbol, point));
+                }
+            }
+        }
+        Ok(level)
+    }
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+    pub fn get_world_size(&self) -> (AreaSize, AreaSize) {
+        self.world_size
+    }
+    pub fn get_walls(&self) -> &Vec<Point<AreaSize>> {
+        &self.walls
+    }
+    pub fn get_spawns(&self) -> &Vec<Spawn> {
+        &self.spawns
+    }
+    pub fn get_food_spawners(&self) -> &Vec<Point<AreaSize>> {
+        &self.food_spawners
+    }
+    pub fn world_config(&self, mut config: Config) -> Config {
+        config.rules.world_size = self.world_size;
+        config.walls = self.walls.clone();
+        config.spawns = self.spawns.clone();
+        config.food_spawners = self.food_spawners.clone();
+        config
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::fixtures;
+    use super::super::world::{CreateError, World};
+    use super::*;
+
+    fn level_world(text: &str) -> Result<World, CreateError> {
+        let level = Level::parse(text).unwrap();
+        let mut config = fixtures::config(0, 1);
+        config.rules.base_snake_tail_size = 3;
+        let mut config = level.world_config(config);
+        config.snakes_controllers = fixtures::greedy_controllers(level.get_spawns().len());
+        World::new(config)
+    }
+
+    fn level_text(spawn_row: &str) -> String {
+        let mut rows = vec!["############"];
+        rows.extend(["#..........#"; 4]);
+        rows.push(spawn_row);
+        rows.extend(["#..........#"; 5]);
+        rows.push("############");
+        rows.join("\n")
+    }
+
+    #[test]
+    fn spawn_glyph_marks_tail() {
+        let mut world = level_world(&level_text("#.>........#")).unwrap();
+        let world_view = world.tick(true);
+        let snake = world_view.get_snakes_info()[&0].get_snake();
+        assert_eq!(snake.head_point(), Point::new(6, 5));
+    }
+
+    #[test]
+    fn spawn_leaving_world_is_blocked() {
+        let world = level_world(&level_text("#<.........#"));
+        assert!(matches!(world, Err(CreateError::BlockedSpawn)));
+    }
+
+    #[test]
+    fn spawn_on_border_is_blocked() {
+        let world = level_world(&level_text("#.......>..#"));
+        assert!(matches!(world, Err(CreateError::BlockedSpawn)));
+    }
+
+    #[test]
+    fn overlapping_spawns_are_blocked() {
+        let world = level_world(&level_text("#.>.<......#"));
+        assert!(matches!(world, Err(CreateError::BlockedSpawn)));
+    }
+
+    #[test]
+    fn unknown_symbol_is_reported() {
+        let level = Level::parse("#?#");
+        assert!(matches!(level, Err(Error::UnknownSymbol('?', _))));
+    }
+}
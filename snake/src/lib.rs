@@ -1,9 +1,21 @@
 extern crate components;
 
+pub mod ai;
+pub mod battlesnake;
+pub mod env;
+#[cfg(test)]
+mod fixtures;
 pub mod game;
+pub mod level;
+pub mod replay;
 pub mod snake;
+pub mod snapshot;
+pub mod tournament;
+pub mod vision;
 pub mod world;
 
 pub type Point<N> = components::point::Point<N>;
 pub type Direction = components::direction::Direction;
 pub type AreaSize = u16;
+
+pub use components::current_time_seed;
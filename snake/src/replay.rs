@@ -0,0 +1,197 @@
+use super::components::direction::Direction;
+use super::snapshot::WorldSnapshot;
+use super::world::{
+    Config, CreateError, EatType, MovePhase, SnakeController, SnakeInfo, World, WorldView,
+};
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+pub const REPLAY_VERSION: u32 = 1;
+
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    Json(serde_json::Error),
+    Version(u32),
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(err: serde_json::Error) -> Self {
+        Self::Json(err)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReplayTick {
+    pub reset: bool,
+    pub directions: BTreeMap<usize, Vec<Direction>>,
+    #[serde(default)]
+    pub timeouts: BTreeMap<usize, Vec<MovePhase>>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Replay {
+    version: u32,
+    config: Config,
+    snakes_numbers: Vec<usize>,
+    #[serde(default)]
+    start_snapshot: Option<WorldSnapshot>,
+    ticks: Vec<ReplayTick>,
+}
+
+impl fmt::Debug for Replay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Replay")
+            .field("version", &self.version)
+            .field("snakes_numbers", &self.snakes_numbers)
+            .field("ticks", &self.ticks.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Replay {
+    pub(crate) fn new(config: &Config) -> Self {
+        let mut snakes_numbers: Vec<usize> = config.snakes_controllers.keys().copied().collect();
+        snakes_numbers.sort();
+        Self {
+            version: REPLAY_VERSION,
+            config: config.clone(),
+            snakes_numbers,
+            start_snapshot: None,
+            ticks: Vec::new(),
+        }
+    }
+    pub(crate) fn from_snapshot(config: &Config, snapshot: &WorldSnapshot) -> Self {
+        Self {
+            start_snapshot: Some(snapshot.clone()),
+            ..Self::new(config)
+        }
+    }
+    pub(crate) fn record_tick(&mut self, reset: bool) {
+        if !self.config.record_replay {
+            return;
+        }
+        self.ticks.push(ReplayTick {
+            reset,
+            directions: BTreeMap::new(),
+            timeouts: BTreeMap::new(),
+        });
+    }
+    pub(crate) fn record_direction(&mut self, snake_number: usize, direction: Direction) {
+        if let Some(tick) = self.ticks.last_mut() {
+            let directions = tick.directions.entry(snake_number).or_default();
+            directions.push(direction);
+        }
+    }
+    pub(crate) fn record_timeout(&mut self, snake_number: usize, phase: MovePhase) {
+        if let Some(tick) = self.ticks.last_mut() {
+            tick.timeouts.entry(snake_number).or_default().push(phase);
+        }
+    }
+    pub fn get_config(&self) -> &Config {
+        &self.config
+    }
+    pub fn get_snakes_numbers(&self) -> &Vec<usize> {
+        &self.snakes_numbers
+    }
+    pub fn get_start_snapshot(&self) -> &Option<WorldSnapshot> {
+        &self.start_snapshot
+    }
+    pub fn get_ticks(&self) -> &Vec<ReplayTick> {
+        &self.ticks
+    }
+    pub fn to_json(&self) -> Result<String, Error> {
+        Ok(serde_json::to_string(self)?)
+    }
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        let replay: Self = serde_json::from_str(json)?;
+        if replay.version != REPLAY_VERSION {
+            return Err(Error::Version(replay.version));
+        }
+        Ok(replay)
+    }
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        fs::write(path, self.to_json()?)?;
+        Ok(())
+    }
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        Self::from_json(&fs::read_to_string(path)?)
+    }
+    pub fn world_config(&self) -> Config {
+        let mut config = self.config.clone();
+        config.snakes_controllers = HashMap::new();
+        config.rules.move_deadline = None;
+        for snake_number in &self.snakes_numbers {
+            let directions = self
+                .ticks
+                .iter()
+                .filter_map(|tick| tick.directions.get(snake_number))
+                .flatten()
+                .copied()
+                .collect();
+            let controller = ReplaySnakeController::new(directions);
+            config
+                .snakes_controllers
+                .insert(*snake_number, Arc::new(Mutex::new(controller)));
+        }
+        config
+    }
+    pub fn simulate<F>(&self, mut on_tick: F) -> Result<(), CreateError>
+    where
+        F: FnMut(&WorldView),
+    {
+        let config = self.world_config();
+        let mut world = match &self.start_snapshot {
+            Some(snapshot) => {
+                let mut snapshot = snapshot.clone();
+                snapshot.config.rules.move_deadline = None;
+                World::from_snapshot(snapshot, config.snakes_controllers)?
+            }
+            None => World::new(config)?,
+        };
+        for tick in &self.ticks {
+            world.set_replayed_timeouts(tick.timeouts.clone());
+            let world_view = world.tick(tick.reset);
+            on_tick(&world_view);
+        }
+        Ok(())
+    }
+}
+
+pub struct ReplaySnakeController {
+    directions: VecDeque<Direction>,
+}
+
+impl ReplaySnakeController {
+    pub fn new(directions: VecDeque<Direction>) -> Self {
+        Self { directions }
+    }
+}
+
+impl SnakeController for ReplaySnakeController {
+    fn snake_will_burn(&mut self, _: &WorldView) {}
+    fn snake_did_burn(&mut self, _: &SnakeInfo, _: &WorldView) {}
+    fn snake_will_move(&mut self, self_info: &SnakeInfo, _: &WorldView) -> Direction {
+        match self.directions.pop_front() {
+            Some(direction) => direction,
+            None => self_info.get_direction().unwrap_or(Direction::Right),
+        }
+    }
+    fn snake_did_move(&mut self, _: &SnakeInfo, _: &WorldView) {}
+    fn snake_will_eat(&mut self, _: EatType, _: &SnakeInfo, _: &WorldView) {}
+    fn snake_did_eat(&mut self, _: EatType, _: &SnakeInfo, _: &WorldView) {}
+    fn snake_will_died(&mut self, _: &SnakeInfo, _: &WorldView) {}
+    fn snake_did_died(&mut self, _: &WorldView) {}
+}
@@ -10,7 +10,7 @@ where
     N: Add<Output = N> + Sub<Output = N> + Copy + Eq + Hash,
 {
     head_point_node: Box<Node<Point<N>>>,
-    is_stomach_not_empty: bool,
+    stomach_size: usize,
 }
 
 impl<N> Snake<N>
@@ -20,9 +20,22 @@ where
     pub fn new(point: Point<N>) -> Self {
         Self {
             head_point_node: Box::new(Node::new(point)),
-            is_stomach_not_empty: false,
+            stomach_size: 0,
         }
     }
+    pub fn from_body_parts_points(points: &[Point<N>], stomach_size: usize) -> Option<Self> {
+        let mut points_iter = points.iter();
+        let mut head_point_node = Node::new(*points_iter.next()?);
+        for point in points_iter {
+            let mut node = Node::new(*point);
+            node.set_next_node(Some(head_point_node));
+            head_point_node = node;
+        }
+        Some(Self {
+            head_point_node: Box::new(head_point_node),
+            stomach_size,
+        })
+    }
     pub fn body_parts_points(&self, include_head: bool) -> Vec<Point<N>> {
         if include_head {
             self.head_point_node.all_nodes_values()
@@ -34,7 +47,25 @@ where
         }
     }
     pub fn fill_stomach_if_empty(&mut self) {
-        self.is_stomach_not_empty = true;
+        if self.stomach_size == 0 {
+            self.stomach_size = 1;
+        }
+    }
+    pub fn fill_stomach(&mut self, size: usize) {
+        self.stomach_size += size;
+    }
+    pub fn is_stomach_not_empty(&self) -> bool {
+        self.stomach_size > 0
+    }
+    pub fn stomach_size(&self) -> usize {
+        self.stomach_size
+    }
+    pub fn shrink(&mut self, size: usize) {
+        let body_points = self.body_parts_points(true);
+        let remove_count = size.min(body_points.len() - 1);
+        if let Some(snake) = Self::from_body_parts_points(&body_points[remove_count..], 0) {
+            self.head_point_node = snake.head_point_node;
+        }
     }
     pub fn head_point(&self) -> Point<N> {
         self.head_point_node.get_value()
@@ -45,29 +76,24 @@ impl<N> Snake<N>
 where
     N: Add<Output = N> + Sub<Output = N> + Copy + Eq + Hash + From<u8>,
 {
-    pub fn next_head_point(&self, move_direction: Direction) -> Point<N> {
+    pub fn next_head_point(
+        &self,
+        move_direction: Direction,
+        wrap_size: Option<(N, N)>,
+    ) -> Point<N> {
         let head_point = self.head_point_node.get_value();
-        let mut x = head_point.x();
-        let mut y = head_point.y();
-        let step_value = N::from(1);
-        match move_direction {
-            Direction::Right => x = x.add(step_value),
-            Direction::Left => x = x.sub(step_value),
-            Direction::Down => y = y.add(step_value),
-            Direction::Up => y = y.sub(step_value),
-        }
-        Point::new(x, y)
+        head_point.moved_to(move_direction, wrap_size)
     }
-    pub fn move_to(&mut self, move_direction: Direction) {
-        let is_body_increased = self.is_stomach_not_empty;
-        self.is_stomach_not_empty = false;
-        let next_head_point = self.next_head_point(move_direction);
+    pub fn move_to(&mut self, move_direction: Direction, wrap_size: Option<(N, N)>) {
+        let is_body_increased = self.stomach_size > 0;
+        self.stomach_size = self.stomach_size.saturating_sub(1);
+        let next_head_point = self.next_head_point(move_direction, wrap_size);
         self.recursive_move_body_to(next_head_point, is_body_increased);
     }
     fn recursive_move_body_to(&mut self, point: Point<N>, add_body_to_end: bool) {
         let mut next_point: Option<Point<N>> = Some(point);
-        self.head_point_node.recursive_run(|node| match next_point {
-            Some(point) => {
+        self.head_point_node.recursive_run(|node| {
+            if let Some(point) = next_point {
                 let current_point = node.get_value();
                 node.set_value(point);
                 match node.get_next_node() {
@@ -82,21 +108,18 @@ where
                     }
                 }
             }
-            None => {}
         });
     }
     pub fn recursive_remove_tail<F>(&mut self, should_remove: F)
     where
         F: Fn(Point<N>) -> bool,
     {
-        self.head_point_node
-            .recursive_run(|node| match node.get_next_node_mut() {
-                Some(next_node) => {
-                    if should_remove(next_node.get_value()) {
-                        node.set_next_node(None);
-                    }
+        self.head_point_node.recursive_run(|node| {
+            if let Some(next_node) = node.get_next_node_mut() {
+                if should_remove(next_node.get_value()) {
+                    node.set_next_node(None);
                 }
-                None => {}
-            });
+            }
+        });
     }
 }
@@ -0,0 +1,82 @@
+use super::components::direction::Direction;
+use super::components::point::Point;
+use super::world::{Config, FoodKind, MatchEnd, SnakeStats, SpeedEffect};
+use super::AreaSize;
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+#[derive(Debug)]
+pub enum Error {
+    Json(serde_json::Error),
+    Binary(bincode::Error),
+    Version(u32),
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(err: serde_json::Error) -> Self {
+        Self::Json(err)
+    }
+}
+
+impl From<bincode::Error> for Error {
+    fn from(err: bincode::Error) -> Self {
+        Self::Binary(err)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnakeSnapshot {
+    pub body_parts_points: Vec<Point<AreaSize>>,
+    pub direction: Option<Direction>,
+    pub stomach_size: usize,
+    pub speed_effect: Option<SpeedEffect>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct WorldSnapshot {
+    pub version: u32,
+    pub config: Config,
+    pub rng_kind: String,
+    pub rng_state: u64,
+    pub tick_number: usize,
+    pub snakes: BTreeMap<usize, SnakeSnapshot>,
+    pub snakes_stats: BTreeMap<usize, SnakeStats>,
+    pub snakes_lives: BTreeMap<usize, usize>,
+    pub snakes_respawns: BTreeMap<usize, usize>,
+    pub match_end: Option<MatchEnd>,
+    pub eat_points: Vec<(Point<AreaSize>, FoodKind)>,
+    pub border_points: Vec<Point<AreaSize>>,
+}
+
+impl WorldSnapshot {
+    pub fn to_json(&self) -> Result<String, Error> {
+        Ok(serde_json::to_string(self)?)
+    }
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        Self::checked(serde_json::from_str(json)?)
+    }
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+        Ok(bincode::serialize(self)?)
+    }
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        Self::checked(bincode::deserialize(bytes)?)
+    }
+    fn checked(snapshot: Self) -> Result<Self, Error> {
+        if snapshot.version != SNAPSHOT_VERSION {
+            return Err(Error::Version(snapshot.version));
+        }
+        Ok(snapshot)
+    }
+}
+
+pub(crate) fn sorted_points<'a, I>(points: I) -> Vec<Point<AreaSize>>
+where
+    I: IntoIterator<Item = &'a Point<AreaSize>>,
+{
+    let mut points: Vec<Point<AreaSize>> = points.into_iter().copied().collect();
+    points.sort_by_key(|point| (point.y(), point.x()));
+    points
+}
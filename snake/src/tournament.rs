@@ -0,0 +1,478 @@
+use super::game::Game;
+use super::world::{self, MatchEnd, SnakeController, SnakeStats, WinCondition};
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+pub const ELO_SCALE: f64 = 400.0;
+
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    Json(serde_json::Error),
+    Create(world::CreateError),
+    Controller(String, String),
+    EntrantsLack,
+    SeedsLack,
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(err: serde_json::Error) -> Self {
+        Self::Json(err)
+    }
+}
+
+impl From<world::CreateError> for Error {
+    fn from(err: world::CreateError) -> Self {
+        Self::Create(err)
+    }
+}
+
+pub type ControllerFactory =
+    Box<dyn Fn(usize) -> Result<Arc<Mutex<dyn SnakeController>>, String> + Send + Sync>;
+
+pub struct Entrant {
+    name: String,
+    factory: ControllerFactory,
+}
+
+impl Entrant {
+    pub fn new<F>(name: &str, factory: F) -> Self
+    where
+        F: Fn(usize) -> Result<Arc<Mutex<dyn SnakeController>>, String> + Send + Sync + 'static,
+    {
+        Self {
+            name: name.to_string(),
+            factory: Box::new(factory),
+        }
+    }
+    pub fn get_name(&self) -> &String {
+        &self.name
+    }
+}
+
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Pairing {
+    RoundRobin,
+    Swiss { rounds: usize },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Config {
+    pub pairing: Pairing,
+    pub seeds: Vec<u64>,
+    #[serde(default = "default_max_ticks")]
+    pub max_ticks: usize,
+    #[serde(default = "default_k_factor")]
+    pub k_factor: f64,
+    #[serde(default = "default_initial_rating")]
+    pub initial_rating: f64,
+    #[serde(default = "default_threads")]
+    pub threads: usize,
+}
+
+pub fn default_max_ticks() -> usize {
+    10000
+}
+
+pub fn default_k_factor() -> f64 {
+    32.0
+}
+
+pub fn default_initial_rating() -> f64 {
+    1500.0
+}
+
+pub fn default_threads() -> usize {
+    1
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GameRecord {
+    pub round: usize,
+    pub seed: u64,
+    pub players: [String; 2],
+    pub winner: Option<String>,
+    pub ticks: usize,
+    pub end: Option<MatchEnd>,
+    pub snakes_stats: [SnakeStats; 2],
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Standing {
+    pub name: String,
+    pub rating: f64,
+    pub points: f64,
+    pub games: usize,
+    pub wins: usize,
+    pub draws: usize,
+    pub losses: usize,
+    pub byes: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TournamentResult {
+    pub games: Vec<GameRecord>,
+    pub leaderboard: Vec<Standing>,
+}
+
+impl TournamentResult {
+    pub fn to_json(&self) -> Result<String, Error> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        fs::write(path, self.to_json()?)?;
+        Ok(())
+    }
+}
+
+pub fn expected_score(rating: f64, opponent_rating: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf((opponent_rating - rating) / ELO_SCALE))
+}
+
+struct GameOutcome {
+    winner: Option<usize>,
+    ticks: usize,
+    end: Option<MatchEnd>,
+    snakes_stats: [SnakeStats; 2],
+}
+
+pub struct Tournament {
+    world_config: world::Config,
+    config: Config,
+    entrants: Vec<Entrant>,
+}
+
+impl Tournament {
+    pub fn new(mut world_config: world::Config, config: Config, entrants: Vec<Entrant>) -> Self {
+        if world_config.rules.win_conditions.is_empty() {
+            world_config
+                .rules
+                .win_conditions
+                .push(WinCondition::LastSnakeStanding);
+        }
+        Self {
+            world_config,
+            config,
+            entrants,
+        }
+    }
+    pub fn run(&self) -> Result<TournamentResult, Error> {
+        if self.entrants.len() < 2 {
+            return Err(Error::EntrantsLack);
+        }
+        if self.config.seeds.is_empty() {
+            return Err(Error::SeedsLack);
+        }
+        let mut standings: Vec<Standing> = self
+            .entrants
+            .iter()
+            .map(|entrant| Standing {
+                name: entrant.name.clone(),
+                rating: self.config.initial_rating,
+                points: 0.0,
+                games: 0,
+                wins: 0,
+                draws: 0,
+                losses: 0,
+                byes: 0,
+            })
+            .collect();
+        let mut played_pairs = HashSet::new();
+        let mut games = Vec::new();
+        let rounds = match self.config.pairing {
+            Pairing::RoundRobin => 1,
+            Pairing::Swiss { rounds } => rounds,
+        };
+        for round in 0..rounds {
+            let pairs = match self.config.pairing {
+                Pairing::RoundRobin => self.round_robin_pairs(),
+                Pairing::Swiss { .. } => {
+                    let (pairs, bye) = Self::swiss_pairs(&standings, &played_pairs);
+                    if let Some(bye) = bye {
+                        standings[bye].points += 1.0;
+                        standings[bye].byes += 1;
+                    }
+                    pairs
+                }
+            };
+            let schedule: Vec<([usize; 2], u64)> = pairs
+                .iter()
+                .flat_map(|pair| {
+                    self.config
+                        .seeds
+                        .iter()
+                        .enumerate()
+                        .map(move |(seed_index, seed)| {
+                            let players = if seed_index % 2 == 0 {
+                                *pair
+                            } else {
+                                [pair[1], pair[0]]
+                            };
+                            (players, *seed)
+                        })
+                })
+                .collect();
+            let outcomes = self.play_games(&schedule)?;
+            for ((players, seed), outcome) in schedule.into_iter().zip(outcomes) {
+                Self::update_standings(&mut standings, players, outcome.winner, &self.config);
+                played_pairs.insert((players[0].min(players[1]), players[0].max(players[1])));
+                games.push(GameRecord {
+                    round,
+                    seed,
+                    players: players.map(|player| self.entrants[player].name.clone()),
+                    winner: outcome
+                        .winner
+                        .map(|winner| self.entrants[players[winner]].name.clone()),
+                    ticks: outcome.ticks,
+                    end: outcome.end,
+                    snakes_stats: outcome.snakes_stats,
+                });
+            }
+        }
+        standings.sort_by(|left, right| {
+            right
+                .points
+                .total_cmp(&left.points)
+                .then(right.rating.total_cmp(&left.rating))
+                .then(left.name.cmp(&right.name))
+        });
+        Ok(TournamentResult {
+            games,
+            leaderboard: standings,
+        })
+    }
+    fn round_robin_pairs(&self) -> Vec<[usize; 2]> {
+        let count = self.entrants.len();
+        (0..count)
+            .flat_map(|first| (first + 1..count).map(move |second| [first, second]))
+            .collect()
+    }
+    fn swiss_pairs(
+        standings: &[Standing],
+        played_pairs: &HashSet<(usize, usize)>,
+    ) -> (Vec<[usize; 2]>, Option<usize>) {
+        let mut order: Vec<usize> = (0..standings.len()).collect();
+        order.sort_by(|left, right| {
+            let (left_standing, right_standing) = (&standings[*left], &standings[*right]);
+            right_standing
+                .points
+                .total_cmp(&left_standing.points)
+                .then(right_standing.rating.total_cmp(&left_standing.rating))
+                .then(left.cmp(right))
+        });
+        let bye = if order.len() % 2 == 1 {
+            let bye_position = order
+                .iter()
+                .rposition(|entrant| standings[*entrant].byes == 0)
+                .unwrap_or(order.len() - 1);
+            Some(order.remove(bye_position))
+        } else {
+            None
+        };
+        let pairs = Self::unplayed_pairs(&order, played_pairs).unwrap_or_else(|| {
+            order
+                .chunks_exact(2)
+                .map(|pair| [pair[0], pair[1]])
+                .collect()
+        });
+        (pairs, bye)
+    }
+    fn unplayed_pairs(
+        order: &[usize],
+        played_pairs: &HashSet<(usize, usize)>,
+    ) -> Option<Vec<[usize; 2]>> {
+        let Some((first, rest)) = order.split_first() else {
+            return Some(Vec::new());
+        };
+        for (position, second) in rest.iter().enumerate() {
+            if played_pairs.contains(&(*first.min(second), *first.max(second))) {
+                continue;
+            }
+            let mut remaining = rest.to_vec();
+            remaining.remove(position);
+            if let Some(mut pairs) = Self::unplayed_pairs(&remaining, played_pairs) {
+                pairs.insert(0, [*first, *second]);
+                return Some(pairs);
+            }
+        }
+        None
+    }
+    fn update_standings(
+        standings: &mut [Standing],
+        players: [usize; 2],
+        winner: Option<usize>,
+        config: &Config,
+    ) {
+        let first_score = match winner {
+            Some(0) => 1.0,
+            Some(_) => 0.0,
+            None => 0.5,
+        };
+        let ratings = players.map(|player| standings[player].rating);
+        let first_expected = expected_score(ratings[0], ratings[1]);
+        let first_change = config.k_factor * (first_score - first_expected);
+        for (side, player) in players.into_iter().enumerate() {
+            let (score, change) = match side {
+                0 => (first_score, first_change),
+                _ => (1.0 - first_score, -first_change),
+            };
+            let standing = &mut standings[player];
+            standing.rating += change;
+            standing.points += score;
+            standing.games += 1;
+            match winner {
+                Some(winner) if winner == side => standing.wins += 1,
+                Some(_) => standing.losses += 1,
+                None => standing.draws += 1,
+            }
+        }
+    }
+    fn play_games(&self, schedule: &[([usize; 2], u64)]) -> Result<Vec<GameOutcome>, Error> {
+        let threads = self.config.threads.clamp(1, schedule.len().max(1));
+        let next_game = AtomicUsize::new(0);
+        let outcomes: Mutex<Vec<Option<Result<GameOutcome, Error>>>> =
+            Mutex::new((0..schedule.len()).map(|_| None).collect());
+        thread::scope(|scope| {
+            for _ in 0..threads {
+                scope.spawn(|| loop {
+                    let game_index = next_game.fetch_add(1, Ordering::SeqCst);
+                    let Some((players, seed)) = schedule.get(game_index) else {
+                        break;
+                    };
+                    let outcome = self.play_game(*players, *seed);
+                    if let Ok(mut outcomes) = outcomes.lock() {
+                        outcomes[game_index] = Some(outcome);
+                    }
+                });
+            }
+        });
+        let outcomes = outcomes.into_inner().unwrap_or_default();
+        outcomes.into_iter().flatten().collect()
+    }
+    fn play_game(&self, players: [usize; 2], seed: u64) -> Result<GameOutcome, Error> {
+        let mut world_config = self.world_config.clone();
+        world_config.seed = seed;
+        world_config.snakes_controllers.clear();
+        for (snake_number, player) in players.iter().enumerate() {
+            let entrant = &self.entrants[*player];
+            let controller = (entrant.factory)(snake_number)
+                .map_err(|reason| Error::Controller(entrant.name.clone(), reason))?;
+            world_config
+                .snakes_controllers
+                .insert(snake_number, controller);
+        }
+        let mut game_match = Game::new_match(world_config)?;
+        let mut ticks = 0;
+        while !game_match.is_over() && ticks < self.config.max_ticks {
+            game_match.step();
+            ticks += 1;
+        }
+        let game_result = game_match.result();
+        let match_result = game_result.match_result;
+        let snakes_stats = [0, 1].map(|snake_number| {
+            let snake_stats = game_result.snakes_stats.get(&snake_number).copied();
+            snake_stats.unwrap_or_default()
+        });
+        Ok(GameOutcome {
+            winner: match match_result.winners.as_slice() {
+                [winner] => Some(*winner),
+                _ => None,
+            },
+            ticks: game_result.ticks,
+            end: match_result.end,
+            snakes_stats,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::ai::greedy::GreedySnakeController;
+    use super::super::fixtures;
+    use super::*;
+
+    fn standing(name: &str, rating: f64, points: f64) -> Standing {
+        Standing {
+            name: name.to_string(),
+            rating,
+            points,
+            games: 0,
+            wins: 0,
+            draws: 0,
+            losses: 0,
+            byes: 0,
+        }
+    }
+
+    fn tournament(entrants: Vec<Entrant>) -> Tournament {
+        let mut world_config = fixtures::config(20, 1);
+        world_config.rules.base_snake_tail_size = 3;
+        let config_json = r#"{"pairing":"RoundRobin","seeds":[1,2],"max_ticks":300}"#;
+        let config: Config = serde_json::from_str(config_json).unwrap();
+        Tournament::new(world_config, config, entrants)
+    }
+
+    fn greedy_entrant(name: &str) -> Entrant {
+        Entrant::new(name, |_| {
+            let controller: Arc<Mutex<dyn SnakeController>> =
+                Arc::new(Mutex::new(GreedySnakeController::new()));
+            Ok(controller)
+        })
+    }
+
+    #[test]
+    fn swiss_pairs_avoid_rematches() {
+        let standings: Vec<Standing> = ["a", "b", "c", "d"]
+            .into_iter()
+            .map(|name| standing(name, 1500.0, 0.0))
+            .collect();
+        let played_pairs = HashSet::from([(0, 1), (1, 3)]);
+        let (pairs, bye) = Tournament::swiss_pairs(&standings, &played_pairs);
+        assert_eq!(pairs, vec![[0, 3], [1, 2]]);
+        assert_eq!(bye, None);
+    }
+
+    #[test]
+    fn factory_error_is_propagated() {
+        let failing_entrant = Entrant::new("broken", |_| Err("no binary".to_string()));
+        let result = tournament(vec![greedy_entrant("greedy"), failing_entrant]).run();
+        match result {
+            Err(Error::Controller(name, reason)) => {
+                assert_eq!(name, "broken");
+                assert_eq!(reason, "no binary");
+            }
+            _ => panic!("controller error expected"),
+        }
+    }
+
+    #[test]
+    fn leaderboard_ranks_points_first() {
+        let entrants = vec![
+            greedy_entrant("first"),
+            greedy_entrant("second"),
+            greedy_entrant("third"),
+        ];
+        let tournament = tournament(entrants);
+        assert_eq!(
+            tournament.world_config.rules.win_conditions,
+            vec![WinCondition::LastSnakeStanding]
+        );
+        let result = tournament.run().unwrap();
+        for pair in result.leaderboard.windows(2) {
+            assert!(pair[0].points >= pair[1].points);
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use super::components::direction::Direction;
+use super::components::point::Point;
+use super::world::{ObjectType, SnakeInfo, WorldView};
+use super::AreaSize;
+
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum GridCell {
+    Unseen,
+    Empty,
+    Object(ObjectType),
+}
+
+pub fn is_visible(
+    center: &Point<AreaSize>,
+    point: &Point<AreaSize>,
+    radius: AreaSize,
+    wrap_size: Option<(AreaSize, AreaSize)>,
+) -> bool {
+    let axis_distance = |from: AreaSize, to: AreaSize, size: Option<AreaSize>| {
+        let distance = from.abs_diff(to) as u32;
+        match size {
+            Some(size) => distance.min(size as u32 - distance),
+            None => distance,
+        }
+    };
+    let dx = axis_distance(center.x(), point.x(), wrap_size.map(|size| size.0));
+    let dy = axis_distance(center.y(), point.y(), wrap_size.map(|size| size.1));
+    let radius = radius as u32;
+    dx * dx + dy * dy <= radius * radius
+}
+
+pub fn offset_point(
+    point: &Point<AreaSize>,
+    offset: (i32, i32),
+    world_size: (AreaSize, AreaSize),
+    wrap_size: Option<(AreaSize, AreaSize)>,
+) -> Option<Point<AreaSize>> {
+    let x = point.x() as i32 + offset.0;
+    let y = point.y() as i32 + offset.1;
+    let (x, y) = match wrap_size {
+        Some((width, height)) => (x.rem_euclid(width as i32), y.rem_euclid(height as i32)),
+        None => (x, y),
+    };
+    if x < 0 || y < 0 || x >= world_size.0 as i32 || y >= world_size.1 as i32 {
+        return None;
+    }
+    Some(Point::new(x as AreaSize, y as AreaSize))
+}
+
+fn heading_offset(direction: Direction, forward: i32, right: i32) -> (i32, i32) {
+    match direction {
+        Direction::Up => (right, -forward),
+        Direction::Down => (-right, forward),
+        Direction::Right => (forward, right),
+        Direction::Left => (-forward, -right),
+    }
+}
+
+fn cell_object(world_view: &WorldView, point: &Point<AreaSize>) -> GridCell {
+    let mut occurrences: Vec<ObjectType> = world_view
+        .get_world_mask()
+        .point_occurrences(point)
+        .into_iter()
+        .collect();
+    occurrences.sort_by_key(|object_type| match object_type {
+        ObjectType::Border => (0, 0),
+        ObjectType::Snake(snake_number) => (1, *snake_number),
+        ObjectType::Eat(food_kind) => (2, *food_kind as usize),
+    });
+    match occurrences.first() {
+        Some(object_type) => GridCell::Object(*object_type),
+        None => GridCell::Empty,
+    }
+}
+
+pub fn egocentric_grid(
+    world_view: &WorldView,
+    self_info: &SnakeInfo,
+    radius: AreaSize,
+) -> Vec<Vec<GridCell>> {
+    let rules = world_view.get_rules();
+    let head_point = self_info.get_snake().head_point();
+    let direction = self_info.get_direction().unwrap_or(Direction::Up);
+    let radius = radius as i32;
+    (0..=radius * 2)
+        .map(|row| {
+            (0..=radius * 2)
+                .map(|column| {
+                    let offset = heading_offset(direction, radius - row, column - radius);
+                    let wrap_size = rules.wrap_size();
+                    let is_seen = |point: &Point<AreaSize>| {
+                        rules.vision_radius.is_none_or(|vision_radius| {
+                            is_visible(&head_point, point, vision_radius, wrap_size)
+                        })
+                    };
+                    match offset_point(&head_point, offset, rules.world_size, wrap_size) {
+                        Some(point) if is_seen(&point) => cell_object(world_view, &point),
+                        Some(_) => GridCell::Unseen,
+                        None => GridCell::Object(ObjectType::Border),
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::fixtures;
+    use super::super::world::World;
+    use super::*;
+
+    #[test]
+    fn grid_tells_unseen_from_empty() {
+        let mut config = fixtures::config(20, 1);
+        config.rules.vision_radius = Some(2);
+        config.snakes_controllers = fixtures::greedy_controllers(1);
+        let mut world = World::new(config).unwrap();
+        let world_view = world.tick(true);
+        let self_info = &world_view.get_snakes_info()[&0];
+        let grid = world_view.get_egocentric_grid(self_info, 4);
+        assert_eq!(grid[4][4], GridCell::Object(ObjectType::Snake(0)));
+        assert_eq!(grid[0][4], GridCell::Unseen);
+        assert!(grid.iter().flatten().any(|cell| *cell == GridCell::Empty));
+    }
+}
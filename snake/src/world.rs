@@ -1,34 +1,204 @@
 use super::components::direction::Direction;
 use super::components::point::Point;
+use super::components::rng::{Rng, SplitMixRng};
 use super::components::world::World as GenericWorld;
-use super::components::{get_rand_in_range, set_rand_current_time_seed};
+use super::replay::Replay;
 use super::snake::Snake;
+use super::snapshot::{sorted_points, SnakeSnapshot, WorldSnapshot, SNAPSHOT_VERSION};
+use super::vision::{self, GridCell};
 use super::AreaSize;
 
-use std::cell::{RefCell, RefMut};
-use std::collections::{HashMap, HashSet};
+use serde::{Deserialize, Serialize};
+
+use std::cell::Cell;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::hash::Hash;
 use std::iter::FromIterator;
-use std::rc::Rc;
+use std::sync::{mpsc, Arc, Mutex, MutexGuard};
+use std::thread;
+use std::time::{Duration, Instant};
 
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Config {
+    pub rules: Rules,
+    pub seed: u64,
+    #[serde(default)]
+    pub walls: Vec<Point<AreaSize>>,
+    #[serde(default)]
+    pub spawns: Vec<Spawn>,
+    #[serde(default)]
+    pub food_spawners: Vec<Point<AreaSize>>,
+    #[serde(default = "default_record_replay")]
+    pub record_replay: bool,
+    #[serde(skip)]
+    pub snakes_controllers: HashMap<usize, Arc<Mutex<dyn SnakeController>>>,
+}
+
+impl Config {
+    fn snakes_spawns(&self) -> Vec<Spawn> {
+        if !self.spawns.is_empty() {
+            return self.spawns.clone();
+        }
+        let mut spawns = Vec::new();
+        for snake_number in 0..self.snakes_controllers.len() as AreaSize {
+            let real_snake_number = snake_number + 1;
+            spawns.push(Spawn {
+                point: Point::new(3, real_snake_number * 3),
+                direction: Direction::Right,
+            });
+        }
+        spawns
+    }
+    pub(crate) fn border_points(&self) -> HashSet<Point<AreaSize>> {
+        let mut border_points = HashSet::new();
+        if self.rules.topology == Topology::Bounded {
+            for x in 0..self.rules.world_size.0 {
+                for y in 0..self.rules.world_size.1 {
+                    let max_x = self.rules.world_size.0 - 1;
+                    let max_y = self.rules.world_size.1 - 1;
+                    if x == 0 || y == 0 || x == max_x || y == max_y {
+                        border_points.insert(Point::new(x, y));
+                    }
+                }
+            }
+        }
+        border_points.extend(self.walls.iter().copied());
+        border_points
+    }
+    pub(crate) fn spawn_body_points(&self, spawn: &Spawn) -> Option<Vec<Point<AreaSize>>> {
+        let mut point = spawn.point;
+        let mut body_points = vec![point];
+        for _ in 0..self.rules.base_snake_tail_size {
+            point = self.moved_point(&point, spawn.direction)?;
+            body_points.push(point);
+        }
+        Some(body_points)
+    }
+    fn moved_point(
+        &self,
+        point: &Point<AreaSize>,
+        direction: Direction,
+    ) -> Option<Point<AreaSize>> {
+        let (width, height) = self.rules.world_size;
+        if point.x() >= width || point.y() >= height {
+            return None;
+        }
+        if self.rules.topology == Topology::Bounded {
+            let is_outside = match direction {
+                Direction::Right => point.x() + 1 >= width,
+                Direction::Left => point.x() == 0,
+                Direction::Down => point.y() + 1 >= height,
+                Direction::Up => point.y() == 0,
+            };
+            if is_outside {
+                return None;
+            }
+        }
+        Some(point.moved_to(direction, self.rules.wrap_size()))
+    }
+    fn snake_controller(
+        &self,
+        id: &usize,
+    ) -> Option<MutexGuard<'_, dyn SnakeController + 'static>> {
+        let controller = self.snakes_controllers.get(id)?;
+        controller.lock().ok()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Rules {
     pub world_size: (AreaSize, AreaSize),
     pub eat_count: AreaSize,
-    pub cut_tails: bool,
+    #[serde(default)]
+    pub collision_rule: CollisionRule,
+    #[serde(default)]
+    pub self_collision_rule: SelfCollisionRule,
     pub base_snake_tail_size: usize,
-    pub snakes_controllers: HashMap<usize, Rc<RefCell<dyn SnakeController>>>,
+    #[serde(default)]
+    pub topology: Topology,
+    #[serde(default = "default_food_weights")]
+    pub food_weights: Vec<(FoodKind, u32)>,
+    #[serde(default = "default_big_food_growth")]
+    pub big_food_growth: usize,
+    #[serde(default = "default_poison_food_shrink")]
+    pub poison_food_shrink: usize,
+    #[serde(default = "default_speed_effect_ticks")]
+    pub speed_effect_ticks: usize,
+    #[serde(default = "default_lives")]
+    pub lives: usize,
+    #[serde(default)]
+    pub respawn_delay: usize,
+    #[serde(default)]
+    pub win_conditions: Vec<WinCondition>,
+    #[serde(default)]
+    pub move_deadline: Option<Duration>,
+    #[serde(default)]
+    pub vision_radius: Option<AreaSize>,
 }
 
-impl Config {
-    fn snake_controller(&self, id: &usize) -> Option<RefMut<dyn SnakeController>> {
-        let controller = self.snakes_controllers.get(id)?;
-        match controller.try_borrow_mut() {
-            Ok(v) => Some(v),
-            Err(_) => None,
+impl Rules {
+    pub fn wrap_size(&self) -> Option<(AreaSize, AreaSize)> {
+        match self.topology {
+            Topology::Bounded => None,
+            Topology::Torus => Some(self.world_size),
         }
     }
 }
 
+pub fn default_food_weights() -> Vec<(FoodKind, u32)> {
+    vec![(FoodKind::Normal, 1)]
+}
+
+pub fn default_big_food_growth() -> usize {
+    3
+}
+
+pub fn default_poison_food_shrink() -> usize {
+    2
+}
+
+pub fn default_speed_effect_ticks() -> usize {
+    20
+}
+
+pub fn default_lives() -> usize {
+    1
+}
+
+pub fn default_record_replay() -> bool {
+    true
+}
+
+#[derive(Debug, Default, Clone, Copy, Hash, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Topology {
+    #[default]
+    Bounded,
+    Torus,
+}
+
+#[derive(Debug, Default, Clone, Copy, Hash, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CollisionRule {
+    BothDie,
+    LongerWins,
+    #[default]
+    AttackerWins,
+    Bounce,
+}
+
+#[derive(Debug, Default, Clone, Copy, Hash, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SelfCollisionRule {
+    Die,
+    #[default]
+    CutTail,
+    PassThrough,
+}
+
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Spawn {
+    pub point: Point<AreaSize>,
+    pub direction: Direction,
+}
+
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
 pub enum CreateError {
     WorldSmall,
@@ -38,18 +208,161 @@ pub enum CreateError {
     TooFewControllers,
     TooManyControllers,
     TooLargeSnakeTail,
+    BlockedSpawn,
+    LivesLack,
+    InvalidSnapshot,
+}
+
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum FoodKind {
+    Normal,
+    Big,
+    Poison,
+    SpeedBoost,
+    Slow,
+}
+
+impl FoodKind {
+    pub const ALL: [FoodKind; 5] = [
+        FoodKind::Normal,
+        FoodKind::Big,
+        FoodKind::Poison,
+        FoodKind::SpeedBoost,
+        FoodKind::Slow,
+    ];
 }
 
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum EatType {
+    Food(FoodKind),
+    Tail(usize),
+}
+
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Speed {
+    Boost,
+    Slow,
+}
+
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpeedEffect {
+    pub speed: Speed,
+    pub ticks_left: usize,
+}
+
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DeathCause {
+    Wall,
+    Obstacle,
+    SelfCollision,
+    SnakeCollision,
+    HeadToHead,
+    Poison,
+}
+
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Death {
+    pub cause: DeathCause,
+    pub killer: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Event {
+    Moved {
+        snake: usize,
+        direction: Direction,
+        head: Point<AreaSize>,
+    },
+    Bounced {
+        snake: usize,
+    },
+    TimedOut {
+        snake: usize,
+    },
+    Ate {
+        snake: usize,
+        kind: FoodKind,
+        point: Point<AreaSize>,
+    },
+    CutTail {
+        cutter: usize,
+        victim: usize,
+        at: Point<AreaSize>,
+    },
+    Died {
+        snake: usize,
+        cause: DeathCause,
+        killer: Option<usize>,
+    },
+    Spawned {
+        snake: usize,
+        head: Point<AreaSize>,
+        direction: Direction,
+    },
+    FoodSpawned {
+        kind: FoodKind,
+        point: Point<AreaSize>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WinCondition {
+    LastSnakeStanding,
+    TargetLength(usize),
+    TickLimit(usize),
+    TimeLimit(Duration),
+    Kills(usize),
+}
+
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MatchEnd {
+    Win(WinCondition),
+    NoSnakesLeft,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MatchResult {
+    pub end: Option<MatchEnd>,
+    pub winners: Vec<usize>,
+    pub ranking: Vec<usize>,
+}
+
+#[derive(Debug, Default, Clone, Copy, Hash, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnakeStats {
+    pub food_eaten: usize,
+    pub tails_cut: usize,
+    pub segments_lost: usize,
+    pub kills: usize,
+    pub ticks_survived: usize,
+    pub max_length: usize,
+    pub deaths: usize,
+    pub timeouts: usize,
+    pub death: Option<Death>,
+}
+
+impl SnakeStats {
+    pub fn score(&self) -> usize {
+        self.food_eaten + self.tails_cut + self.kills
+    }
+}
+
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub enum ObjectType {
     Border,
     Snake(usize),
-    Eat,
+    Eat(FoodKind),
+}
+
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MovePhase {
+    Boost,
+    Common,
 }
 
 pub struct SnakeInfo {
     snake: Snake<AreaSize>,
     direction: Option<Direction>,
+    speed_effect: Option<SpeedEffect>,
 }
 
 impl SnakeInfo {
@@ -59,13 +372,69 @@ impl SnakeInfo {
     pub fn get_direction(&self) -> &Option<Direction> {
         &self.direction
     }
+    pub fn get_speed_effect(&self) -> &Option<SpeedEffect> {
+        &self.speed_effect
+    }
+    fn should_move(&self, phase: MovePhase, tick_number: usize) -> bool {
+        let speed = self.speed_effect.map(|effect| effect.speed);
+        match (phase, speed) {
+            (MovePhase::Boost, Some(Speed::Boost)) => true,
+            (MovePhase::Boost, _) => false,
+            (MovePhase::Common, Some(Speed::Slow)) => tick_number.is_multiple_of(2),
+            (MovePhase::Common, _) => true,
+        }
+    }
+    fn snapshot(&self) -> SnakeSnapshot {
+        SnakeSnapshot {
+            body_parts_points: self.snake.body_parts_points(true),
+            direction: self.direction,
+            stomach_size: self.snake.stomach_size(),
+            speed_effect: self.speed_effect,
+        }
+    }
+    fn from_snapshot(snake_snapshot: &SnakeSnapshot) -> Option<Self> {
+        let snake = Snake::from_body_parts_points(
+            &snake_snapshot.body_parts_points,
+            snake_snapshot.stomach_size,
+        )?;
+        Some(Self {
+            snake,
+            direction: snake_snapshot.direction,
+            speed_effect: snake_snapshot.speed_effect,
+        })
+    }
+}
+
+struct Vision {
+    world_mask: GenericWorld<ObjectType, AreaSize>,
+    snakes_info: HashMap<usize, SnakeInfo>,
+    border_points: HashSet<Point<AreaSize>>,
+    eat_points: HashMap<Point<AreaSize>, FoodKind>,
+    snakes_stats: HashMap<usize, SnakeStats>,
+    snakes_lives: HashMap<usize, usize>,
+    events: Vec<Event>,
+}
+
+struct DetachedView {
+    vision: Vision,
+    match_end: Option<MatchEnd>,
+    rules: Rules,
+    snakes_numbers: Vec<usize>,
+    tick_number: usize,
 }
 
 pub struct WorldView<'a> {
     world_mask: &'a GenericWorld<ObjectType, AreaSize>,
     snakes_info: &'a HashMap<usize, SnakeInfo>,
     border_points: &'a HashSet<Point<AreaSize>>,
-    eat_points: &'a HashSet<Point<AreaSize>>,
+    eat_points: &'a HashMap<Point<AreaSize>, FoodKind>,
+    snakes_stats: &'a HashMap<usize, SnakeStats>,
+    snakes_lives: &'a HashMap<usize, usize>,
+    match_end: &'a Option<MatchEnd>,
+    events: &'a Vec<Event>,
+    rules: &'a Rules,
+    snakes_numbers: &'a Vec<usize>,
+    tick_number: usize,
 }
 
 impl<'a> WorldView<'a> {
@@ -75,6 +444,44 @@ impl<'a> WorldView<'a> {
             snakes_info: &world.snakes_info,
             border_points: &world.border_points,
             eat_points: &world.eat_points,
+            snakes_stats: &world.snakes_stats,
+            snakes_lives: &world.snakes_lives,
+            match_end: &world.match_end,
+            events: &world.events,
+            rules: &world.config.rules,
+            snakes_numbers: &world.snakes_numbers,
+            tick_number: world.tick_number,
+        }
+    }
+    fn detached(view: &'a DetachedView) -> Self {
+        Self {
+            world_mask: &view.vision.world_mask,
+            snakes_info: &view.vision.snakes_info,
+            border_points: &view.vision.border_points,
+            eat_points: &view.vision.eat_points,
+            snakes_stats: &view.vision.snakes_stats,
+            snakes_lives: &view.vision.snakes_lives,
+            match_end: &view.match_end,
+            events: &view.vision.events,
+            rules: &view.rules,
+            snakes_numbers: &view.snakes_numbers,
+            tick_number: view.tick_number,
+        }
+    }
+    fn for_snake(world: &'a World, vision: &'a Option<Vision>) -> Self {
+        let world_view = Self::new(world);
+        match vision {
+            Some(vision) => Self {
+                world_mask: &vision.world_mask,
+                snakes_info: &vision.snakes_info,
+                border_points: &vision.border_points,
+                eat_points: &vision.eat_points,
+                snakes_stats: &vision.snakes_stats,
+                snakes_lives: &vision.snakes_lives,
+                events: &vision.events,
+                ..world_view
+            },
+            None => world_view,
         }
     }
     pub fn get_world_mask(&self) -> &'a GenericWorld<ObjectType, AreaSize> {
@@ -86,18 +493,46 @@ impl<'a> WorldView<'a> {
     pub fn get_border_points(&self) -> &'a HashSet<Point<AreaSize>> {
         self.border_points
     }
-    pub fn get_eat_points(&self) -> &'a HashSet<Point<AreaSize>> {
+    pub fn get_eat_points(&self) -> &'a HashMap<Point<AreaSize>, FoodKind> {
         self.eat_points
     }
+    pub fn get_snakes_stats(&self) -> &'a HashMap<usize, SnakeStats> {
+        self.snakes_stats
+    }
+    pub fn get_snakes_lives(&self) -> &'a HashMap<usize, usize> {
+        self.snakes_lives
+    }
+    pub fn get_match_end(&self) -> &'a Option<MatchEnd> {
+        self.match_end
+    }
+    pub fn get_events(&self) -> &'a Vec<Event> {
+        self.events
+    }
+    pub fn get_rules(&self) -> &'a Rules {
+        self.rules
+    }
+    pub fn get_snakes_numbers(&self) -> &'a Vec<usize> {
+        self.snakes_numbers
+    }
+    pub fn get_tick_number(&self) -> usize {
+        self.tick_number
+    }
+    pub fn get_egocentric_grid(
+        &self,
+        self_info: &SnakeInfo,
+        radius: AreaSize,
+    ) -> Vec<Vec<GridCell>> {
+        vision::egocentric_grid(self, self_info, radius)
+    }
 }
 
-pub trait SnakeController {
+pub trait SnakeController: Send {
     fn snake_will_burn(&mut self, world_view: &WorldView);
     fn snake_did_burn(&mut self, self_info: &SnakeInfo, world_view: &WorldView);
     fn snake_will_move(&mut self, self_info: &SnakeInfo, world_view: &WorldView) -> Direction;
     fn snake_did_move(&mut self, self_info: &SnakeInfo, world_view: &WorldView);
-    fn snake_will_eat(&mut self, good_eat: bool, self_info: &SnakeInfo, world_view: &WorldView);
-    fn snake_did_eat(&mut self, good_eat: bool, self_info: &SnakeInfo, world_view: &WorldView);
+    fn snake_will_eat(&mut self, eat: EatType, self_info: &SnakeInfo, world_view: &WorldView);
+    fn snake_did_eat(&mut self, eat: EatType, self_info: &SnakeInfo, world_view: &WorldView);
     fn snake_will_died(&mut self, self_info: &SnakeInfo, world_view: &WorldView);
     fn snake_did_died(&mut self, world_view: &WorldView);
 }
@@ -106,119 +541,434 @@ pub struct World {
     world_mask: GenericWorld<ObjectType, AreaSize>,
     snakes_info: HashMap<usize, SnakeInfo>,
     border_points: HashSet<Point<AreaSize>>,
-    eat_points: HashSet<Point<AreaSize>>,
+    eat_points: HashMap<Point<AreaSize>, FoodKind>,
+    snakes_stats: HashMap<usize, SnakeStats>,
+    snakes_lives: HashMap<usize, usize>,
+    snakes_respawns: BTreeMap<usize, usize>,
+    match_end: Option<MatchEnd>,
+    events: Vec<Event>,
+    tick_number: usize,
+    start_time: Option<Instant>,
+    move_workers: HashMap<usize, MoveWorker>,
+    rng: Box<dyn Rng>,
+    replay: Replay,
+    replayed_timeouts: BTreeMap<usize, Vec<MovePhase>>,
+    snakes_numbers: Vec<usize>,
     config: Config,
 }
 
+#[derive(Default)]
+struct MoveDecision {
+    direction: Option<Direction>,
+    is_timed_out: bool,
+}
+
+struct MoveRequest {
+    self_info: SnakeInfo,
+    view: Arc<DetachedView>,
+}
+
+struct MoveWorker {
+    requests: mpsc::Sender<MoveRequest>,
+    replies: mpsc::Receiver<Direction>,
+    is_busy: Cell<bool>,
+}
+
+impl MoveWorker {
+    fn spawn(controller: Arc<Mutex<dyn SnakeController>>) -> Self {
+        let (requests, requests_receiver) = mpsc::channel::<MoveRequest>();
+        let (replies_sender, replies) = mpsc::channel();
+        thread::spawn(move || {
+            for request in requests_receiver {
+                let mut controller = match controller.lock() {
+                    Ok(controller) => controller,
+                    Err(_) => break,
+                };
+                let world_view = WorldView::detached(&request.view);
+                let direction = controller.snake_will_move(&request.self_info, &world_view);
+                if replies_sender.send(direction).is_err() {
+                    break;
+                }
+            }
+        });
+        Self {
+            requests,
+            replies,
+            is_busy: Cell::new(false),
+        }
+    }
+}
+
+struct SnakesMoveResult {
+    points_move_vectors: HashMap<Point<AreaSize>, HashMap<usize, Direction>>,
+    previous_snakes: BTreeMap<usize, SnakeSnapshot>,
+}
+
 struct SnakesInteractionsDetectResult {
-    snakes_to_remove: HashSet<usize>,
-    snakes_that_ate_food: HashMap<usize, Point<AreaSize>>,
-    snakes_that_bit_tail: HashMap<usize, (usize, Point<AreaSize>)>,
+    snakes_to_remove: BTreeMap<usize, Death>,
+    snakes_to_bounce: BTreeSet<usize>,
+    snakes_that_ate_food: BTreeMap<usize, Point<AreaSize>>,
+    snakes_that_bit_tail: BTreeMap<usize, (usize, Point<AreaSize>)>,
 }
 
 impl World {
     pub fn new(config: Config) -> Result<Self, CreateError> {
-        if config.world_size.0 < 10 || config.world_size.1 < 10 {
+        let rng = Box::new(SplitMixRng::new(config.seed));
+        Self::new_with_rng(config, rng)
+    }
+    pub fn new_with_rng(config: Config, rng: Box<dyn Rng>) -> Result<Self, CreateError> {
+        let min_world_size = if config.spawns.is_empty() { 10 } else { 3 };
+        if config.rules.world_size.0 < min_world_size || config.rules.world_size.1 < min_world_size
+        {
             return Err(CreateError::WorldSmall);
         }
-        if config.world_size.0 > 1000 || config.world_size.1 > 1000 {
+        if config.rules.world_size.0 > 1000 || config.rules.world_size.1 > 1000 {
             return Err(CreateError::WorldLarge);
         }
-        if config.eat_count < 1 {
+        if config.rules.eat_count < 1 {
             return Err(CreateError::FoodLack);
         }
-        if config.eat_count > 100 {
+        if config.rules.eat_count > 100 {
             return Err(CreateError::FoodExcess);
         }
-        if config.snakes_controllers.len() < 1 {
+        if config
+            .rules
+            .food_weights
+            .iter()
+            .all(|(_, weight)| *weight == 0)
+        {
+            return Err(CreateError::FoodLack);
+        }
+        if config.rules.lives < 1 {
+            return Err(CreateError::LivesLack);
+        }
+        if config.snakes_controllers.is_empty() {
             return Err(CreateError::TooFewControllers);
         }
-        if config.world_size.1 <= ((config.snakes_controllers.len() + 1) * 3) as AreaSize {
-            return Err(CreateError::TooManyControllers);
+        if config.spawns.is_empty() {
+            if config.rules.world_size.1 <= ((config.snakes_controllers.len() + 1) * 3) as AreaSize
+            {
+                return Err(CreateError::TooManyControllers);
+            }
+            if (4 + config.rules.base_snake_tail_size + 1 + 4) as AreaSize
+                > config.rules.world_size.0
+            {
+                return Err(CreateError::TooLargeSnakeTail);
+            }
+        } else {
+            if config.spawns.len() < config.snakes_controllers.len() {
+                return Err(CreateError::TooManyControllers);
+            }
+            let border_points = config.border_points();
+            let mut spawns_points = HashSet::new();
+            for spawn in &config.spawns {
+                let body_points = config
+                    .spawn_body_points(spawn)
+                    .ok_or(CreateError::BlockedSpawn)?;
+                for point in body_points {
+                    let in_world = point.x() < config.rules.world_size.0
+                        && point.y() < config.rules.world_size.1;
+                    if !in_world || border_points.contains(&point) || !spawns_points.insert(point) {
+                        return Err(CreateError::BlockedSpawn);
+                    }
+                }
+            }
         }
-        if (4 + config.base_snake_tail_size + 1 + 4) as AreaSize > config.world_size.0 {
-            return Err(CreateError::TooLargeSnakeTail);
+        let mut snakes_numbers: Vec<usize> = config.snakes_controllers.keys().copied().collect();
+        snakes_numbers.sort();
+        let mut move_workers = HashMap::new();
+        if config.rules.move_deadline.is_some() {
+            for (snake_number, controller) in &config.snakes_controllers {
+                move_workers.insert(*snake_number, MoveWorker::spawn(controller.clone()));
+            }
         }
         Ok(Self {
             world_mask: GenericWorld::new(),
             snakes_info: HashMap::new(),
             border_points: HashSet::new(),
-            eat_points: HashSet::new(),
+            eat_points: HashMap::new(),
+            snakes_stats: HashMap::new(),
+            snakes_lives: HashMap::new(),
+            snakes_respawns: BTreeMap::new(),
+            match_end: None,
+            events: Vec::new(),
+            tick_number: 0,
+            start_time: None,
+            move_workers,
+            rng,
+            replay: Replay::new(&config),
+            replayed_timeouts: BTreeMap::new(),
+            snakes_numbers,
             config,
         })
     }
     fn spawn_border(&mut self) {
-        let border_points: HashSet<Point<AreaSize>> = {
-            let mut border_points = HashSet::new();
-            for x in 0..self.config.world_size.0 {
-                for y in 0..self.config.world_size.1 {
-                    let max_x = self.config.world_size.0 - 1;
-                    let max_y = self.config.world_size.1 - 1;
-                    if x == 0 || y == 0 || x == max_x || y == max_y {
-                        border_points.insert(Point::new(x, y));
-                    }
-                }
-            }
-            border_points
-        };
-        self.border_points = border_points;
+        self.border_points = self.config.border_points();
         self.world_mask
             .set_layer(ObjectType::Border, self.border_points.clone());
     }
     fn spawn_snakes(&mut self) {
-        let snakes: HashMap<usize, Snake<AreaSize>> = {
-            let mut snakes = HashMap::new();
-            for snake_number in 0..self.config.snakes_controllers.len() as AreaSize {
-                let real_snake_number = snake_number + 1;
-                let snake_number = snake_number as usize;
-                let mut snake = Snake::new(Point::new(3, real_snake_number * 3));
-                for _ in 0..self.config.base_snake_tail_size {
-                    snake.fill_stomach_if_empty();
-                    snake.move_to(Direction::Right);
-                }
-                snakes.insert(snake_number, snake);
+        let snakes: BTreeMap<usize, (Snake<AreaSize>, Direction)> = {
+            let mut snakes = BTreeMap::new();
+            let mut snakes_numbers: Vec<usize> =
+                self.config.snakes_controllers.keys().copied().collect();
+            snakes_numbers.sort();
+            let spawns = self.config.snakes_spawns();
+            for (snake_number, spawn) in snakes_numbers.into_iter().zip(spawns) {
+                let body_points = self.config.spawn_body_points(&spawn).unwrap_or_default();
+                if let Some(snake) = Snake::from_body_parts_points(&body_points, 0) {
+                    snakes.insert(snake_number, (snake, spawn.direction));
+                }
             }
             snakes
         };
-        for (snake_number, snake) in snakes {
-            if let Some(mut controller) = self.config.snake_controller(&snake_number) {
-                let world_view = WorldView::new(self);
-                controller.snake_will_burn(&world_view);
-            }
-            let points = HashSet::from_iter(snake.body_parts_points(true).clone());
-            let snake_info = SnakeInfo {
-                snake,
-                direction: None,
-            };
-            self.snakes_info.insert(snake_number, snake_info);
-            self.world_mask
-                .set_layer(ObjectType::Snake(snake_number.clone()), points);
-            if let Some(snake_info) = self.snakes_info.get(&snake_number) {
-                if let Some(mut controller) = self.config.snake_controller(&snake_number) {
-                    let world_view = WorldView::new(self);
-                    controller.snake_did_burn(snake_info, &world_view);
+        self.snakes_respawns.clear();
+        self.match_end = None;
+        for snake_number in self.config.snakes_controllers.keys() {
+            self.snakes_lives
+                .insert(*snake_number, self.config.rules.lives);
+        }
+        for (snake_number, (snake, direction)) in snakes {
+            self.burn_snake(snake_number, snake, direction);
+        }
+    }
+    fn burn_snake(&mut self, snake_number: usize, snake: Snake<AreaSize>, direction: Direction) {
+        if let Some(mut controller) = self.snake_controller(&snake_number) {
+            let vision = self.snake_vision(snake_number);
+            let world_view = WorldView::for_snake(self, &vision);
+            controller.snake_will_burn(&world_view);
+        }
+        let points = HashSet::from_iter(snake.body_parts_points(true).clone());
+        let snake_info = SnakeInfo {
+            snake,
+            direction: Some(direction),
+            speed_effect: None,
+        };
+        self.snakes_info.insert(snake_number, snake_info);
+        self.world_mask
+            .set_layer(ObjectType::Snake(snake_number), points);
+        if let Some(snake_info) = self.snakes_info.get(&snake_number) {
+            self.events.push(Event::Spawned {
+                snake: snake_number,
+                head: snake_info.snake.head_point(),
+                direction,
+            });
+        }
+        if let Some(snake_info) = self.snakes_info.get(&snake_number) {
+            if let Some(mut controller) = self.snake_controller(&snake_number) {
+                let vision = self.snake_vision(snake_number);
+                let world_view = WorldView::for_snake(self, &vision);
+                controller.snake_did_burn(snake_info, &world_view);
+            }
+        }
+    }
+    fn respawn_snakes(&mut self) {
+        let snakes_numbers: Vec<usize> = self
+            .snakes_respawns
+            .iter()
+            .filter(|(_, respawn_tick)| **respawn_tick <= self.tick_number)
+            .map(|(snake_number, _)| *snake_number)
+            .collect();
+        if snakes_numbers.is_empty() {
+            return;
+        }
+        let spawns = self.config.snakes_spawns();
+        let mut controllers_numbers: Vec<usize> =
+            self.config.snakes_controllers.keys().copied().collect();
+        controllers_numbers.sort();
+        for snake_number in snakes_numbers {
+            let own_spawn_index = controllers_numbers
+                .iter()
+                .position(|number| *number == snake_number)
+                .unwrap_or(0);
+            let safe_spawn = spawns
+                .iter()
+                .cycle()
+                .skip(own_spawn_index)
+                .take(spawns.len())
+                .find(|spawn| self.is_safe_spawn(spawn))
+                .copied();
+            if let Some(spawn) = safe_spawn {
+                let body_points = self.config.spawn_body_points(&spawn).unwrap_or_default();
+                if let Some(snake) = Snake::from_body_parts_points(&body_points, 0) {
+                    self.snakes_respawns.remove(&snake_number);
+                    if let Some(snake_stats) = self.snakes_stats.get_mut(&snake_number) {
+                        snake_stats.death = None;
+                    }
+                    for body_point in &body_points {
+                        self.eat_points.remove(body_point);
+                    }
+                    self.set_eat_layers();
+                    self.burn_snake(snake_number, snake, spawn.direction);
+                }
+            }
+        }
+    }
+    fn is_safe_spawn(&self, spawn: &Spawn) -> bool {
+        let mut points = match self.config.spawn_body_points(spawn) {
+            Some(points) => points,
+            None => return false,
+        };
+        let next_head_point = points
+            .last()
+            .and_then(|head_point| self.config.moved_point(head_point, spawn.direction));
+        match next_head_point {
+            Some(next_head_point) => points.push(next_head_point),
+            None => return false,
+        }
+        points.iter().all(|point| {
+            let objects = self.world_mask.point_occurrences(point);
+            objects
+                .iter()
+                .all(|object| matches!(object, ObjectType::Eat(_)))
+        })
+    }
+    fn snake_controller(
+        &self,
+        snake_number: &usize,
+    ) -> Option<MutexGuard<'_, dyn SnakeController + 'static>> {
+        if self.move_workers.is_empty() {
+            return self.config.snake_controller(snake_number);
+        }
+        // A controller still busy with a timed out move is skipped, so it misses
+        // the callbacks of the ticks it spends deciding.
+        let controller = self.config.snakes_controllers.get(snake_number)?;
+        controller.try_lock().ok()
+    }
+    fn snake_move_decision(
+        &self,
+        snake_number: usize,
+        shared_view: &mut Option<Arc<DetachedView>>,
+    ) -> MoveDecision {
+        let snake_info = match self.snakes_info.get(&snake_number) {
+            Some(snake_info) => snake_info,
+            None => return MoveDecision::default(),
+        };
+        let (move_deadline, move_worker) = match (
+            self.config.rules.move_deadline,
+            self.move_workers.get(&snake_number),
+        ) {
+            (Some(move_deadline), Some(move_worker)) => (move_deadline, move_worker),
+            _ => {
+                let direction = self.snake_controller(&snake_number).map(|mut controller| {
+                    let vision = self.snake_vision(snake_number);
+                    let world_view = WorldView::for_snake(self, &vision);
+                    controller.snake_will_move(snake_info, &world_view)
+                });
+                return MoveDecision {
+                    direction,
+                    ..MoveDecision::default()
+                };
+            }
+        };
+        let timed_out_decision = Self::timed_out_decision(snake_info);
+        if move_worker.is_busy.get() {
+            match move_worker.replies.try_recv() {
+                Ok(_) => move_worker.is_busy.set(false),
+                Err(_) => return timed_out_decision,
+            }
+        }
+        let view = match self.config.rules.vision_radius {
+            Some(_) => self.detached_view(snake_number).map(Arc::new),
+            None => {
+                if shared_view.is_none() {
+                    *shared_view = self.detached_view(snake_number).map(Arc::new);
                 }
+                shared_view.clone()
+            }
+        };
+        let request = match (SnakeInfo::from_snapshot(&snake_info.snapshot()), view) {
+            (Some(self_info), Some(view)) => MoveRequest { self_info, view },
+            _ => return MoveDecision::default(),
+        };
+        if move_worker.requests.send(request).is_err() {
+            return timed_out_decision;
+        }
+        match move_worker.replies.recv_timeout(move_deadline) {
+            Ok(direction) => MoveDecision {
+                direction: Some(direction),
+                ..MoveDecision::default()
+            },
+            Err(_) => {
+                move_worker.is_busy.set(true);
+                timed_out_decision
             }
         }
     }
-    fn snakes_move(&mut self) -> HashMap<Point<AreaSize>, HashSet<Direction>> {
-        let mut points_move_vectors = HashMap::<Point<AreaSize>, HashSet<Direction>>::new();
+    fn timed_out_decision(snake_info: &SnakeInfo) -> MoveDecision {
+        MoveDecision {
+            direction: snake_info.direction,
+            is_timed_out: true,
+        }
+    }
+    fn detached_view(&self, snake_number: usize) -> Option<DetachedView> {
+        let vision = match self.snake_vision(snake_number) {
+            Some(vision) => vision,
+            None => {
+                let mut snakes_info = HashMap::new();
+                for (number, snake_info) in &self.snakes_info {
+                    snakes_info.insert(*number, SnakeInfo::from_snapshot(&snake_info.snapshot())?);
+                }
+                Vision {
+                    world_mask: self.world_mask.clone(),
+                    snakes_info,
+                    border_points: self.border_points.clone(),
+                    eat_points: self.eat_points.clone(),
+                    snakes_stats: self.snakes_stats.clone(),
+                    snakes_lives: self.snakes_lives.clone(),
+                    events: self.events.clone(),
+                }
+            }
+        };
+        Some(DetachedView {
+            vision,
+            match_end: self.match_end,
+            rules: self.config.rules.clone(),
+            snakes_numbers: self.snakes_numbers.clone(),
+            tick_number: self.tick_number,
+        })
+    }
+    fn snakes_move(&mut self, phase: MovePhase) -> SnakesMoveResult {
+        let mut points_move_vectors = HashMap::<Point<AreaSize>, HashMap<usize, Direction>>::new();
+        let mut previous_snakes = BTreeMap::<usize, SnakeSnapshot>::new();
         let snakes_numbers = {
             let mut snakes_numbers = Vec::<usize>::new();
-            for (key, snake_number) in self.snakes_info.keys().enumerate() {
-                snakes_numbers.insert(key, snake_number.clone());
+            for (snake_number, snake_info) in &self.snakes_info {
+                if snake_info.should_move(phase, self.tick_number) {
+                    snakes_numbers.push(*snake_number);
+                }
             }
+            snakes_numbers.sort();
             snakes_numbers
         };
-        for snake_number in snakes_numbers {
-            let mut new_direction: Option<Direction> = None;
+        let mut snakes_directions = Vec::<(usize, Option<Direction>)>::new();
+        let mut shared_view = None;
+        for snake_number in &snakes_numbers {
+            let snake_number = *snake_number;
+            let is_replayed_timeout = self
+                .replayed_timeouts
+                .get(&snake_number)
+                .is_some_and(|phases| phases.contains(&phase));
+            let decision = match self.snakes_info.get(&snake_number) {
+                Some(snake_info) if is_replayed_timeout => Self::timed_out_decision(snake_info),
+                _ => self.snake_move_decision(snake_number, &mut shared_view),
+            };
             if let Some(snake_info) = self.snakes_info.get(&snake_number) {
-                new_direction = snake_info.direction;
-                if let Some(mut controller) = self.config.snake_controller(&snake_number) {
-                    let world_view = WorldView::new(self);
-                    let controller_direction = controller.snake_will_move(snake_info, &world_view);
+                let mut new_direction = snake_info.direction;
+                if decision.is_timed_out {
+                    self.snakes_stats.entry(snake_number).or_default().timeouts += 1;
+                    self.events.push(Event::TimedOut {
+                        snake: snake_number,
+                    });
+                    self.replay.record_timeout(snake_number, phase);
+                }
+                if let Some(controller_direction) = decision.direction {
+                    if !decision.is_timed_out {
+                        self.replay
+                            .record_direction(snake_number, controller_direction);
+                    }
                     if let Some(snake_direction) = snake_info.direction {
-                        let have_tail = snake_info.snake.body_parts_points(false).len() > 0;
+                        let have_tail = !snake_info.snake.body_parts_points(false).is_empty();
                         if controller_direction.reverse() != snake_direction || !have_tail {
                             new_direction = Some(controller_direction);
                         }
@@ -229,47 +979,88 @@ impl World {
                 if let Some(direction) = new_direction {
                     let head_point = snake_info.snake.head_point();
                     if let Some(vector_directions) = points_move_vectors.get_mut(&head_point) {
-                        vector_directions.insert(direction);
+                        vector_directions.insert(snake_number, direction);
                     } else {
-                        let mut vector_directions = HashSet::new();
-                        vector_directions.insert(direction);
-                        points_move_vectors.insert(head_point.clone(), vector_directions);
+                        let mut vector_directions = HashMap::new();
+                        vector_directions.insert(snake_number, direction);
+                        points_move_vectors.insert(head_point, vector_directions);
                     }
                 }
+                snakes_directions.push((snake_number, new_direction));
             }
+        }
+        for (snake_number, new_direction) in snakes_directions {
             if let Some(snake_info) = self.snakes_info.get_mut(&snake_number) {
+                previous_snakes.insert(snake_number, snake_info.snapshot());
                 snake_info.direction = new_direction;
                 if let Some(direction) = new_direction {
-                    snake_info.snake.move_to(direction);
+                    snake_info
+                        .snake
+                        .move_to(direction, self.config.rules.wrap_size());
+                    self.events.push(Event::Moved {
+                        snake: snake_number,
+                        direction,
+                        head: snake_info.snake.head_point(),
+                    });
                 }
+                let points = HashSet::from_iter(snake_info.snake.body_parts_points(true));
+                self.world_mask
+                    .set_layer(ObjectType::Snake(snake_number), points);
             }
+        }
+        for snake_number in snakes_numbers {
             if let Some(snake_info) = self.snakes_info.get(&snake_number) {
-                let points = HashSet::from_iter(snake_info.snake.body_parts_points(true).clone());
-                self.world_mask
-                    .set_layer(ObjectType::Snake(snake_number.clone()), points);
-                if let Some(mut controller) = self.config.snake_controller(&snake_number) {
-                    let world_view = WorldView::new(self);
+                if let Some(mut controller) = self.snake_controller(&snake_number) {
+                    let vision = self.snake_vision(snake_number);
+                    let world_view = WorldView::for_snake(self, &vision);
                     controller.snake_did_move(snake_info, &world_view);
                 }
             }
         }
-        points_move_vectors
+        SnakesMoveResult {
+            points_move_vectors,
+            previous_snakes,
+        }
     }
+    /// Resolves collisions after every moving snake has made its step:
+    /// - head-to-head: heads ending on the same cell collide under `collision_rule`;
+    /// - swap-through: heads exchanging cells pass through each other, same as head-to-head;
+    /// - head-to-neck: a head entering the cell right behind another head is a body hit;
+    /// - body hit: the attacker's head on another body collides under `collision_rule`,
+    ///   only the attacker dies under `BothDie`;
+    /// - bounce: a snake whose head lands on a bounced snake's restored body bounces too;
+    /// - self hit: the head on its own body is resolved by `self_collision_rule`;
+    /// - a cell left by a tail in this step is free, so snakes may follow tails closely.
     fn snakes_interactions_detect(
         &mut self,
-        points_move_vectors: &HashMap<Point<AreaSize>, HashSet<Direction>>,
+        points_move_vectors: &HashMap<Point<AreaSize>, HashMap<usize, Direction>>,
+        previous_snakes: &BTreeMap<usize, SnakeSnapshot>,
     ) -> SnakesInteractionsDetectResult {
-        let mut snakes_to_remove = HashSet::<usize>::new();
-        let mut snakes_that_ate_food = HashMap::<usize, Point<AreaSize>>::new();
-        let mut snakes_that_bit_tail = HashMap::<usize, (usize, Point<AreaSize>)>::new();
-        for (snake_number, snake_info) in &self.snakes_info {
+        let mut snakes_to_remove = BTreeMap::<usize, Death>::new();
+        let mut snakes_to_bounce = BTreeSet::<usize>::new();
+        let mut snakes_that_ate_food = BTreeMap::<usize, Point<AreaSize>>::new();
+        let mut snakes_that_bit_tail = BTreeMap::<usize, (usize, Point<AreaSize>)>::new();
+        let mut head_collisions = BTreeSet::<(usize, usize)>::new();
+        let mut body_collisions = BTreeMap::<usize, (usize, Point<AreaSize>)>::new();
+        let mut remove_snake = |snake_number: usize, cause: DeathCause, killer: Option<usize>| {
+            let death = Death { cause, killer };
+            snakes_to_remove.entry(snake_number).or_insert(death);
+        };
+        let mut snakes_numbers: Vec<usize> = self.snakes_info.keys().copied().collect();
+        snakes_numbers.sort();
+        for snake_number in snakes_numbers {
+            let snake_info = &self.snakes_info[&snake_number];
             let body_points = snake_info.snake.body_parts_points(true);
             let head_point = snake_info.snake.head_point();
             if let Some(vector_directions) = points_move_vectors.get(&head_point) {
-                for vector_direction in vector_directions {
+                for (vector_snake_number, vector_direction) in vector_directions {
                     let vector_reversed_direction = vector_direction.reverse();
                     if Some(vector_reversed_direction) == snake_info.direction {
-                        snakes_to_remove.insert(snake_number.clone());
+                        let pair = (
+                            snake_number.min(*vector_snake_number),
+                            snake_number.max(*vector_snake_number),
+                        );
+                        head_collisions.insert(pair);
                     }
                 }
             }
@@ -277,168 +1068,1469 @@ impl World {
             for body_point in body_points {
                 if head_point == body_point {
                     if head_points_catch {
-                        if self.config.cut_tails {
-                            let tail_info = (snake_number.clone(), body_point.clone());
-                            snakes_that_bit_tail.insert(snake_number.clone(), tail_info);
-                        } else {
-                            snakes_to_remove.insert(snake_number.clone());
+                        match self.config.rules.self_collision_rule {
+                            SelfCollisionRule::Die => {
+                                remove_snake(snake_number, DeathCause::SelfCollision, None);
+                            }
+                            SelfCollisionRule::CutTail => {
+                                let tail_info = (snake_number, body_point);
+                                snakes_that_bit_tail.insert(snake_number, tail_info);
+                            }
+                            SelfCollisionRule::PassThrough => {}
                         }
                     } else {
                         head_points_catch = true;
                     }
                 }
-                for object in self.world_mask.point_occurrences(&body_point) {
+                let mut objects: Vec<ObjectType> = self
+                    .world_mask
+                    .point_occurrences(&body_point)
+                    .into_iter()
+                    .collect();
+                objects.sort();
+                for object in objects {
                     match object {
                         ObjectType::Snake(number) => {
-                            if number != *snake_number {
-                                if self.config.cut_tails {
-                                    if let Some(other_snake_info) = self.snakes_info.get(&number) {
-                                        if other_snake_info.snake.head_point() == head_point {
-                                            snakes_to_remove.insert(snake_number.clone());
-                                            continue;
-                                        }
-                                    }
-                                    if body_point == head_point {
-                                        let tail_info = (number.clone(), body_point.clone());
-                                        snakes_that_bit_tail
-                                            .insert(snake_number.clone(), tail_info);
-                                    }
-                                } else {
-                                    snakes_to_remove.insert(snake_number.clone());
+                            if number == snake_number || body_point != head_point {
+                                continue;
+                            }
+                            match self.snakes_info.get(&number) {
+                                Some(other) if other.snake.head_point() == head_point => {
+                                    let pair = (snake_number.min(number), snake_number.max(number));
+                                    head_collisions.insert(pair);
+                                }
+                                _ => {
+                                    body_collisions
+                                        .entry(snake_number)
+                                        .or_insert((number, body_point));
                                 }
                             }
                         }
-                        ObjectType::Eat => {
+                        ObjectType::Eat(_) => {
                             if body_point == head_point {
-                                snakes_that_ate_food
-                                    .insert(snake_number.clone(), body_point.clone());
+                                snakes_that_ate_food.insert(snake_number, body_point);
                             } else {
-                                snakes_to_remove.insert(snake_number.clone());
+                                remove_snake(snake_number, DeathCause::Obstacle, None);
                             }
                         }
                         ObjectType::Border => {
-                            snakes_to_remove.insert(snake_number.clone());
+                            remove_snake(snake_number, DeathCause::Wall, None);
                         }
                     }
                 }
             }
         }
+        let snake_length = |snake_number: &usize| match self.snakes_info.get(snake_number) {
+            Some(snake_info) => snake_info.snake.body_parts_points(true).len(),
+            None => 0,
+        };
+        for (first, second) in head_collisions {
+            let (first_length, second_length) = (snake_length(&first), snake_length(&second));
+            match self.config.rules.collision_rule {
+                CollisionRule::LongerWins if first_length > second_length => {
+                    remove_snake(second, DeathCause::HeadToHead, Some(first));
+                }
+                CollisionRule::LongerWins if second_length > first_length => {
+                    remove_snake(first, DeathCause::HeadToHead, Some(second));
+                }
+                CollisionRule::Bounce => {
+                    snakes_to_bounce.insert(first);
+                    snakes_to_bounce.insert(second);
+                }
+                _ => {
+                    remove_snake(first, DeathCause::HeadToHead, Some(second));
+                    remove_snake(second, DeathCause::HeadToHead, Some(first));
+                }
+            }
+        }
+        for (attacker, (victim, body_point)) in body_collisions {
+            match self.config.rules.collision_rule {
+                CollisionRule::BothDie | CollisionRule::LongerWins => {
+                    remove_snake(attacker, DeathCause::SnakeCollision, Some(victim));
+                }
+                CollisionRule::AttackerWins => {
+                    snakes_that_bit_tail.insert(attacker, (victim, body_point));
+                }
+                CollisionRule::Bounce => {
+                    snakes_to_bounce.insert(attacker);
+                }
+            }
+        }
+        for snake_number in snakes_to_remove.keys().chain(&snakes_to_bounce) {
+            snakes_that_ate_food.remove(snake_number);
+            snakes_that_bit_tail.remove(snake_number);
+        }
+        snakes_to_bounce.retain(|snake_number| !snakes_to_remove.contains_key(snake_number));
+        self.bounce_cascade_detect(&mut snakes_to_bounce, &snakes_to_remove, previous_snakes);
+        for snake_number in &snakes_to_bounce {
+            snakes_that_ate_food.remove(snake_number);
+            snakes_that_bit_tail.remove(snake_number);
+        }
         SnakesInteractionsDetectResult {
             snakes_to_remove,
+            snakes_to_bounce,
             snakes_that_ate_food,
             snakes_that_bit_tail,
         }
     }
-    fn handle_snakes_to_remove(&mut self, snakes_to_remove: HashSet<usize>) {
-        for snake_remove_number in snakes_to_remove {
+    fn bounce_cascade_detect(
+        &self,
+        snakes_to_bounce: &mut BTreeSet<usize>,
+        snakes_to_remove: &BTreeMap<usize, Death>,
+        previous_snakes: &BTreeMap<usize, SnakeSnapshot>,
+    ) {
+        loop {
+            let bounced_points: HashSet<Point<AreaSize>> = snakes_to_bounce
+                .iter()
+                .filter_map(|snake_number| previous_snakes.get(snake_number))
+                .flat_map(|snake_snapshot| snake_snapshot.body_parts_points.iter().copied())
+                .collect();
+            let mut snakes_numbers: Vec<usize> = self.snakes_info.keys().copied().collect();
+            snakes_numbers.sort();
+            let blocked_snakes: Vec<usize> = snakes_numbers
+                .into_iter()
+                .filter(|snake_number| {
+                    !snakes_to_bounce.contains(snake_number)
+                        && !snakes_to_remove.contains_key(snake_number)
+                        && previous_snakes.contains_key(snake_number)
+                        && bounced_points
+                            .contains(&self.snakes_info[snake_number].snake.head_point())
+                })
+                .collect();
+            if blocked_snakes.is_empty() {
+                break;
+            }
+            snakes_to_bounce.extend(blocked_snakes);
+        }
+    }
+    fn handle_snakes_to_remove(&mut self, snakes_to_remove: BTreeMap<usize, Death>) {
+        for (snake_remove_number, death) in snakes_to_remove {
             if let Some(to_remove_snake_info) = self.snakes_info.get(&snake_remove_number) {
-                if let Some(mut controller) = self.config.snake_controller(&snake_remove_number) {
-                    let world_view = WorldView::new(self);
-                    controller.snake_will_died(&to_remove_snake_info, &world_view);
+                if let Some(mut controller) = self.snake_controller(&snake_remove_number) {
+                    let vision = self.snake_vision(snake_remove_number);
+                    let world_view = WorldView::for_snake(self, &vision);
+                    controller.snake_will_died(to_remove_snake_info, &world_view);
+                }
+            }
+            if let Some(killer) = death.killer {
+                self.snakes_stats.entry(killer).or_default().kills += 1;
+            }
+            self.events.push(Event::Died {
+                snake: snake_remove_number,
+                cause: death.cause,
+                killer: death.killer,
+            });
+            let snake_stats = self.snakes_stats.entry(snake_remove_number).or_default();
+            snake_stats.deaths += 1;
+            snake_stats.death = Some(death);
+            if let Some(lives) = self.snakes_lives.get_mut(&snake_remove_number) {
+                *lives = lives.saturating_sub(1);
+                if *lives > 0 {
+                    let respawn_tick = self.tick_number + self.config.rules.respawn_delay + 1;
+                    self.snakes_respawns
+                        .insert(snake_remove_number, respawn_tick);
                 }
             }
             self.snakes_info.remove(&snake_remove_number);
             self.world_mask
                 .remove_layer(&ObjectType::Snake(snake_remove_number));
-            if let Some(mut controller) = self.config.snake_controller(&snake_remove_number) {
-                let world_view = WorldView::new(self);
+            if let Some(mut controller) = self.snake_controller(&snake_remove_number) {
+                let vision = self.snake_vision(snake_remove_number);
+                let world_view = WorldView::for_snake(self, &vision);
                 controller.snake_did_died(&world_view);
             }
         }
     }
+    fn handle_snakes_to_bounce(
+        &mut self,
+        snakes_to_bounce: BTreeSet<usize>,
+        previous_snakes: BTreeMap<usize, SnakeSnapshot>,
+    ) {
+        for snake_number in snakes_to_bounce {
+            let snake_info = match previous_snakes.get(&snake_number) {
+                Some(snake_snapshot) => SnakeInfo::from_snapshot(snake_snapshot),
+                None => None,
+            };
+            if let Some(snake_info) = snake_info {
+                let points = HashSet::from_iter(snake_info.snake.body_parts_points(true));
+                self.world_mask
+                    .set_layer(ObjectType::Snake(snake_number), points);
+                self.snakes_info.insert(snake_number, snake_info);
+                self.events.push(Event::Bounced {
+                    snake: snake_number,
+                });
+            }
+        }
+    }
     fn handle_snakes_that_bit_tail(
         &mut self,
-        snakes_that_bit_tail: HashMap<usize, (usize, Point<AreaSize>)>,
+        snakes_that_bit_tail: BTreeMap<usize, (usize, Point<AreaSize>)>,
     ) {
         for (snake, (cut_snake, body_point)) in snakes_that_bit_tail {
             if let Some(snake_info) = self.snakes_info.get(&snake) {
-                if let Some(mut controller) = self.config.snake_controller(&snake) {
-                    let world_view = WorldView::new(self);
-                    controller.snake_will_eat(false, &snake_info, &world_view);
+                if let Some(mut controller) = self.snake_controller(&snake) {
+                    let vision = self.snake_vision(snake);
+                    let world_view = WorldView::for_snake(self, &vision);
+                    controller.snake_will_eat(EatType::Tail(cut_snake), snake_info, &world_view);
                 }
             }
             if let Some(snake_info) = self.snakes_info.get_mut(&snake) {
                 snake_info.snake.fill_stomach_if_empty();
             }
             if let Some(cut_snake_info) = self.snakes_info.get_mut(&cut_snake) {
+                let length_before_cut = cut_snake_info.snake.body_parts_points(true).len();
                 cut_snake_info
                     .snake
                     .recursive_remove_tail(|p| p == body_point);
                 let body_points = cut_snake_info.snake.body_parts_points(true).clone();
+                let segments_lost = length_before_cut - body_points.len();
+                self.snakes_stats
+                    .entry(cut_snake)
+                    .or_default()
+                    .segments_lost += segments_lost;
+                if cut_snake != snake {
+                    self.snakes_stats.entry(snake).or_default().tails_cut += 1;
+                }
+                self.events.push(Event::CutTail {
+                    cutter: snake,
+                    victim: cut_snake,
+                    at: body_point,
+                });
                 let points = HashSet::from_iter(body_points);
                 self.world_mask
-                    .set_layer(ObjectType::Snake(cut_snake.clone()), points);
+                    .set_layer(ObjectType::Snake(cut_snake), points);
             }
             if let Some(snake_info) = self.snakes_info.get(&snake) {
-                if let Some(mut controller) = self.config.snake_controller(&snake) {
-                    let world_view = WorldView::new(self);
-                    controller.snake_did_eat(false, &snake_info, &world_view);
+                if let Some(mut controller) = self.snake_controller(&snake) {
+                    let vision = self.snake_vision(snake);
+                    let world_view = WorldView::for_snake(self, &vision);
+                    controller.snake_did_eat(EatType::Tail(cut_snake), snake_info, &world_view);
                 }
             }
         }
     }
     fn handle_snakes_that_ate_food(
         &mut self,
-        snakes_that_ate_food: HashMap<usize, Point<AreaSize>>,
+        snakes_that_ate_food: BTreeMap<usize, Point<AreaSize>>,
     ) {
+        let mut poisoned_snakes = BTreeMap::new();
         for (snakes_feeding, eat_point) in snakes_that_ate_food {
+            let food_kind = match self.eat_points.get(&eat_point) {
+                Some(food_kind) => *food_kind,
+                None => continue,
+            };
             if let Some(snake_info) = self.snakes_info.get(&snakes_feeding) {
-                if let Some(mut controller) = self.config.snake_controller(&snakes_feeding) {
-                    let world_view = WorldView::new(self);
-                    controller.snake_will_eat(true, &snake_info, &world_view);
+                if let Some(mut controller) = self.snake_controller(&snakes_feeding) {
+                    let vision = self.snake_vision(snakes_feeding);
+                    let world_view = WorldView::for_snake(self, &vision);
+                    controller.snake_will_eat(EatType::Food(food_kind), snake_info, &world_view);
                 }
             }
             if let Some(snake_info) = self.snakes_info.get_mut(&snakes_feeding) {
-                snake_info.snake.fill_stomach_if_empty();
-                if self.eat_points.remove(&eat_point) {
-                    self.world_mask
-                        .set_layer(ObjectType::Eat, self.eat_points.clone());
+                let length_before_eat = snake_info.snake.body_parts_points(true).len();
+                match food_kind {
+                    FoodKind::Normal => snake_info.snake.fill_stomach(1),
+                    FoodKind::Big => snake_info
+                        .snake
+                        .fill_stomach(self.config.rules.big_food_growth),
+                    FoodKind::Poison => {
+                        if snake_info.snake.body_parts_points(false).is_empty() {
+                            let death = Death {
+                                cause: DeathCause::Poison,
+                                killer: None,
+                            };
+                            poisoned_snakes.insert(snakes_feeding, death);
+                        }
+                        snake_info
+                            .snake
+                            .shrink(self.config.rules.poison_food_shrink)
+                    }
+                    FoodKind::SpeedBoost | FoodKind::Slow => {
+                        let speed = match food_kind {
+                            FoodKind::SpeedBoost => Speed::Boost,
+                            _ => Speed::Slow,
+                        };
+                        snake_info.speed_effect = Some(SpeedEffect {
+                            speed,
+                            ticks_left: self.config.rules.speed_effect_ticks,
+                        });
+                    }
+                }
+                let body_points = snake_info.snake.body_parts_points(true);
+                let snake_stats = self.snakes_stats.entry(snakes_feeding).or_default();
+                snake_stats.food_eaten += 1;
+                self.events.push(Event::Ate {
+                    snake: snakes_feeding,
+                    kind: food_kind,
+                    point: eat_point,
+                });
+                snake_stats.segments_lost += length_before_eat.saturating_sub(body_points.len());
+                let points = HashSet::from_iter(body_points);
+                self.world_mask
+                    .set_layer(ObjectType::Snake(snakes_feeding), points);
+                if self.eat_points.remove(&eat_point).is_some() {
+                    self.set_eat_layers();
                 }
             }
             if let Some(snake_info) = self.snakes_info.get(&snakes_feeding) {
-                if let Some(mut controller) = self.config.snake_controller(&snakes_feeding) {
-                    let world_view = WorldView::new(self);
-                    controller.snake_did_eat(true, &snake_info, &world_view);
+                if let Some(mut controller) = self.snake_controller(&snakes_feeding) {
+                    let vision = self.snake_vision(snakes_feeding);
+                    let world_view = WorldView::for_snake(self, &vision);
+                    controller.snake_did_eat(EatType::Food(food_kind), snake_info, &world_view);
                 }
             }
         }
+        self.handle_snakes_to_remove(poisoned_snakes);
     }
-    fn spawn_eat(&mut self) {
-        let eat_to_spawn = self.config.eat_count - self.eat_points.len() as AreaSize;
-        unsafe {
-            let _ = set_rand_current_time_seed();
+    fn is_free_point(&self, point: &Point<AreaSize>) -> bool {
+        self.world_mask.point_occurrences(point).is_empty() && !self.eat_points.contains_key(point)
+    }
+    fn random_free_point(&mut self) -> Option<Point<AreaSize>> {
+        const RANDOM_ATTEMPTS: usize = 64;
+        let min_point = match self.config.rules.topology {
+            Topology::Bounded => 1,
+            Topology::Torus => 0,
+        };
+        let max_point = (
+            (self.config.rules.world_size.0 - 1) as i64,
+            (self.config.rules.world_size.1 - 1) as i64,
+        );
+        for _ in 0..RANDOM_ATTEMPTS {
+            let point = if self.config.food_spawners.is_empty() {
+                let x = self.rng.rand_in_range(min_point, max_point.0);
+                let y = self.rng.rand_in_range(min_point, max_point.1);
+                Point::new(x as AreaSize, y as AreaSize)
+            } else {
+                let last_index = (self.config.food_spawners.len() - 1) as i64;
+                self.config.food_spawners[self.rng.rand_in_range(0, last_index) as usize]
+            };
+            if self.is_free_point(&point) {
+                return Some(point);
+            }
         }
+        let free_points: Vec<Point<AreaSize>> = if self.config.food_spawners.is_empty() {
+            let mut free_points = Vec::new();
+            for y in min_point..=max_point.1 {
+                for x in min_point..=max_point.0 {
+                    let point = Point::new(x as AreaSize, y as AreaSize);
+                    if self.is_free_point(&point) {
+                        free_points.push(point);
+                    }
+                }
+            }
+            free_points
+        } else {
+            let food_spawners = self.config.food_spawners.iter();
+            food_spawners
+                .filter(|p| self.is_free_point(p))
+                .copied()
+                .collect()
+        };
+        if free_points.is_empty() {
+            return None;
+        }
+        let last_index = (free_points.len() - 1) as i64;
+        Some(free_points[self.rng.rand_in_range(0, last_index) as usize])
+    }
+    fn random_food_kind(&mut self) -> FoodKind {
+        let total_weight: u32 = self.config.rules.food_weights.iter().map(|(_, w)| w).sum();
+        let mut choice = self.rng.rand_in_range(0, total_weight as i64 - 1) as u32;
+        for (food_kind, weight) in &self.config.rules.food_weights {
+            if choice < *weight {
+                return *food_kind;
+            }
+            choice -= weight;
+        }
+        FoodKind::Normal
+    }
+    fn snake_vision(&self, snake_number: usize) -> Option<Vision> {
+        let radius = self.config.rules.vision_radius?;
+        let wrap_size = self.config.rules.wrap_size();
+        let head_point = self
+            .snakes_info
+            .get(&snake_number)
+            .map(|snake_info| snake_info.snake.head_point());
+        let is_visible = |point: &Point<AreaSize>| {
+            head_point
+                .is_some_and(|head_point| vision::is_visible(&head_point, point, radius, wrap_size))
+        };
+        let mut world_mask = GenericWorld::new();
+        let mut snakes_info = HashMap::new();
+        for (number, snake_info) in &self.snakes_info {
+            let is_own = *number == snake_number;
+            let body_points = snake_info.snake.body_parts_points(true);
+            let visible_points = body_points
+                .iter()
+                .filter(|point| is_own || is_visible(point))
+                .copied()
+                .collect();
+            world_mask.set_layer(ObjectType::Snake(*number), visible_points);
+            let mut snake_snapshot = snake_info.snapshot();
+            if !is_own {
+                let visible_length = body_points
+                    .iter()
+                    .rev()
+                    .take_while(|point| is_visible(point))
+                    .count();
+                if visible_length == 0 {
+                    continue;
+                }
+                snake_snapshot
+                    .body_parts_points
+                    .drain(..body_points.len() - visible_length);
+            }
+            if let Some(visible_snake_info) = SnakeInfo::from_snapshot(&snake_snapshot) {
+                snakes_info.insert(*number, visible_snake_info);
+            }
+        }
+        let border_points: HashSet<Point<AreaSize>> = self
+            .border_points
+            .iter()
+            .filter(|point| is_visible(point))
+            .copied()
+            .collect();
+        world_mask.set_layer(ObjectType::Border, border_points.clone());
+        let eat_points: HashMap<Point<AreaSize>, FoodKind> = self
+            .eat_points
+            .iter()
+            .filter(|(point, _)| is_visible(point))
+            .map(|(point, food_kind)| (*point, *food_kind))
+            .collect();
+        for food_kind in FoodKind::ALL {
+            let points = eat_points
+                .iter()
+                .filter(|(_, kind)| **kind == food_kind)
+                .map(|(point, _)| *point)
+                .collect();
+            world_mask.set_layer(ObjectType::Eat(food_kind), points);
+        }
+        let events = self
+            .events
+            .iter()
+            .filter(|event| match event {
+                Event::Moved { snake, head, .. } | Event::Spawned { snake, head, .. } => {
+                    *snake == snake_number || is_visible(head)
+                }
+                Event::Bounced { snake } | Event::TimedOut { snake } => {
+                    *snake == snake_number || snakes_info.contains_key(snake)
+                }
+                Event::Ate { snake, point, .. } => *snake == snake_number || is_visible(point),
+                Event::CutTail { cutter, victim, at } => {
+                    *cutter == snake_number || *victim == snake_number || is_visible(at)
+                }
+                Event::Died { snake, killer, .. } => {
+                    *snake == snake_number || *killer == Some(snake_number)
+                }
+                Event::FoodSpawned { point, .. } => is_visible(point),
+            })
+            .copied()
+            .collect();
+        let snakes_stats = self
+            .snakes_stats
+            .iter()
+            .filter(|(number, _)| **number == snake_number)
+            .map(|(number, snake_stats)| (*number, *snake_stats))
+            .collect();
+        let snakes_lives = self
+            .snakes_lives
+            .iter()
+            .filter(|(number, _)| **number == snake_number)
+            .map(|(number, lives)| (*number, *lives))
+            .collect();
+        Some(Vision {
+            world_mask,
+            snakes_info,
+            border_points,
+            eat_points,
+            snakes_stats,
+            snakes_lives,
+            events,
+        })
+    }
+    fn set_eat_layers(&mut self) {
+        for food_kind in FoodKind::ALL {
+            let points = self
+                .eat_points
+                .iter()
+                .filter(|(_, kind)| **kind == food_kind)
+                .map(|(point, _)| *point)
+                .collect();
+            self.world_mask
+                .set_layer(ObjectType::Eat(food_kind), points);
+        }
+    }
+    fn spawn_eat(&mut self) {
+        let eat_to_spawn = self
+            .config
+            .rules
+            .eat_count
+            .saturating_sub(self.eat_points.len() as AreaSize);
         for _ in 0..eat_to_spawn {
-            loop {
-                let point: Point<AreaSize> = unsafe {
-                    let x = get_rand_in_range(1, (self.config.world_size.0 - 1) as i32);
-                    let y = get_rand_in_range(1, (self.config.world_size.1 - 1) as i32);
-                    Point::new(x as u16, y as u16)
-                };
-                if self.world_mask.point_occurrences(&point).len() == 0 {
-                    self.eat_points.insert(point);
-                    break;
+            match self.random_free_point() {
+                Some(point) => {
+                    let food_kind = self.random_food_kind();
+                    self.eat_points.insert(point, food_kind);
+                    self.events.push(Event::FoodSpawned {
+                        kind: food_kind,
+                        point,
+                    });
                 }
+                None => break,
             }
         }
-        self.world_mask
-            .set_layer(ObjectType::Eat, self.eat_points.clone());
+        self.set_eat_layers();
+    }
+    fn update_snakes_stats(&mut self) {
+        for (snake_number, snake_info) in &self.snakes_info {
+            let snake_stats = self.snakes_stats.entry(*snake_number).or_default();
+            let length = snake_info.snake.body_parts_points(true).len();
+            snake_stats.ticks_survived += 1;
+            snake_stats.max_length = snake_stats.max_length.max(length);
+        }
+    }
+    fn expire_speed_effects(&mut self) {
+        for snake_info in self.snakes_info.values_mut() {
+            if let Some(speed_effect) = &mut snake_info.speed_effect {
+                speed_effect.ticks_left = speed_effect.ticks_left.saturating_sub(1);
+                if speed_effect.ticks_left == 0 {
+                    snake_info.speed_effect = None;
+                }
+            }
+        }
+    }
+    fn is_contender(&self, snake_number: &usize) -> bool {
+        self.snakes_lives.get(snake_number).copied().unwrap_or(0) > 0
+    }
+    fn snake_length(&self, snake_number: &usize) -> usize {
+        match self.snakes_info.get(snake_number) {
+            Some(snake_info) => snake_info.snake.body_parts_points(true).len(),
+            None => 0,
+        }
+    }
+    fn match_metric(&self, snake_number: &usize, condition: Option<WinCondition>) -> usize {
+        let snake_stats = self.snakes_stats.get(snake_number).copied();
+        let snake_stats = snake_stats.unwrap_or_default();
+        match condition {
+            Some(WinCondition::LastSnakeStanding) => snake_stats.ticks_survived,
+            Some(WinCondition::TargetLength(_)) => self.snake_length(snake_number),
+            Some(WinCondition::Kills(_)) => snake_stats.kills,
+            Some(WinCondition::TickLimit(_) | WinCondition::TimeLimit(_)) | None => {
+                snake_stats.score()
+            }
+        }
+    }
+    fn has_reached(&self, snake_number: &usize, condition: Option<WinCondition>) -> bool {
+        match condition {
+            Some(WinCondition::TargetLength(length)) => self.snake_length(snake_number) >= length,
+            Some(WinCondition::Kills(kills)) => self
+                .snakes_stats
+                .get(snake_number)
+                .is_some_and(|snake_stats| snake_stats.kills >= kills),
+            _ => false,
+        }
+    }
+    fn match_end_detect(&self) -> Option<MatchEnd> {
+        let snakes_numbers = self.config.snakes_controllers.keys();
+        let contenders_count = snakes_numbers
+            .clone()
+            .filter(|snake_number| self.is_contender(snake_number))
+            .count();
+        for condition in &self.config.rules.win_conditions {
+            let is_decided = match condition {
+                WinCondition::LastSnakeStanding => {
+                    self.config.snakes_controllers.len() > 1 && contenders_count == 1
+                }
+                WinCondition::TargetLength(_) | WinCondition::Kills(_) => snakes_numbers
+                    .clone()
+                    .any(|snake_number| self.has_reached(snake_number, Some(*condition))),
+                WinCondition::TickLimit(ticks) => self.tick_number >= *ticks,
+                WinCondition::TimeLimit(duration) => self
+                    .start_time
+                    .is_some_and(|start_time| start_time.elapsed() >= *duration),
+            };
+            if is_decided {
+                return Some(MatchEnd::Win(*condition));
+            }
+        }
+        if contenders_count == 0 {
+            return Some(MatchEnd::NoSnakesLeft);
+        }
+        None
+    }
+    pub fn match_result(&self) -> MatchResult {
+        let condition = match self.match_end {
+            Some(MatchEnd::Win(condition)) => Some(condition),
+            _ => None,
+        };
+        let rank_key = |snake_number: &usize| {
+            let snake_stats = self.snakes_stats.get(snake_number).copied();
+            let snake_stats = snake_stats.unwrap_or_default();
+            (
+                self.has_reached(snake_number, condition),
+                self.is_contender(snake_number),
+                self.match_metric(snake_number, condition),
+                snake_stats.score(),
+                snake_stats.ticks_survived,
+                self.snake_length(snake_number),
+            )
+        };
+        let mut ranking: Vec<usize> = self.config.snakes_controllers.keys().copied().collect();
+        ranking.sort_by(|first, second| {
+            let order = rank_key(second).cmp(&rank_key(first));
+            order.then(first.cmp(second))
+        });
+        let winners = match (self.match_end, ranking.first()) {
+            (Some(MatchEnd::Win(WinCondition::LastSnakeStanding)), _) => ranking
+                .iter()
+                .copied()
+                .filter(|snake_number| self.is_contender(snake_number))
+                .collect(),
+            (Some(MatchEnd::Win(WinCondition::TargetLength(_) | WinCondition::Kills(_))), _) => {
+                ranking
+                    .iter()
+                    .copied()
+                    .filter(|snake_number| self.has_reached(snake_number, condition))
+                    .collect()
+            }
+            (Some(MatchEnd::Win(condition)), Some(leader)) => {
+                let leader_metric = self.match_metric(leader, Some(condition));
+                ranking
+                    .iter()
+                    .copied()
+                    .take_while(|snake_number| {
+                        self.match_metric(snake_number, Some(condition)) == leader_metric
+                    })
+                    .collect()
+            }
+            _ => Vec::new(),
+        };
+        MatchResult {
+            end: self.match_end,
+            winners,
+            ranking,
+        }
+    }
+    pub fn is_match_over(&self) -> bool {
+        self.match_end.is_some()
+    }
+    pub fn get_match_end(&self) -> &Option<MatchEnd> {
+        &self.match_end
     }
-    pub fn tick(&mut self, reset: bool) -> WorldView {
+    pub fn get_replay(&self) -> &Replay {
+        &self.replay
+    }
+    pub fn get_tick_number(&self) -> usize {
+        self.tick_number
+    }
+    pub fn get_snakes_stats(&self) -> &HashMap<usize, SnakeStats> {
+        &self.snakes_stats
+    }
+    pub fn snapshot(&self) -> WorldSnapshot {
+        let mut snakes = BTreeMap::new();
+        for (snake_number, snake_info) in &self.snakes_info {
+            snakes.insert(*snake_number, snake_info.snapshot());
+        }
+        WorldSnapshot {
+            version: SNAPSHOT_VERSION,
+            config: self.config.clone(),
+            rng_kind: self.rng.get_kind().to_string(),
+            rng_state: self.rng.get_state(),
+            tick_number: self.tick_number,
+            snakes,
+            snakes_stats: BTreeMap::from_iter(self.snakes_stats.clone()),
+            snakes_lives: BTreeMap::from_iter(self.snakes_lives.clone()),
+            snakes_respawns: self.snakes_respawns.clone(),
+            match_end: self.match_end,
+            eat_points: sorted_points(self.eat_points.keys())
+                .into_iter()
+                .map(|point| (point, self.eat_points[&point]))
+                .collect(),
+            border_points: sorted_points(&self.border_points),
+        }
+    }
+    pub fn from_snapshot(
+        snapshot: WorldSnapshot,
+        snakes_controllers: HashMap<usize, Arc<Mutex<dyn SnakeController>>>,
+    ) -> Result<Self, CreateError> {
+        let rng = Box::new(SplitMixRng::new(snapshot.rng_state));
+        Self::from_snapshot_with_rng(snapshot, snakes_controllers, rng)
+    }
+    pub fn from_snapshot_with_rng(
+        snapshot: WorldSnapshot,
+        snakes_controllers: HashMap<usize, Arc<Mutex<dyn SnakeController>>>,
+        mut rng: Box<dyn Rng>,
+    ) -> Result<Self, CreateError> {
+        if snapshot.version != SNAPSHOT_VERSION
+            || snapshot.rng_kind != rng.get_kind()
+            || !Self::is_snapshot_consistent(&snapshot)
+        {
+            return Err(CreateError::InvalidSnapshot);
+        }
+        rng.set_state(snapshot.rng_state);
+        let mut config = snapshot.config.clone();
+        config.snakes_controllers = snakes_controllers;
+        let mut world = Self::new_with_rng(config, rng)?;
+        world.replay = Replay::from_snapshot(&world.config, &snapshot);
+        for (snake_number, snake_snapshot) in snapshot.snakes {
+            let snake_info =
+                SnakeInfo::from_snapshot(&snake_snapshot).ok_or(CreateError::InvalidSnapshot)?;
+            let points = HashSet::from_iter(snake_snapshot.body_parts_points);
+            world
+                .world_mask
+                .set_layer(ObjectType::Snake(snake_number), points);
+            world.snakes_info.insert(snake_number, snake_info);
+        }
+        world.tick_number = snapshot.tick_number;
+        world.snakes_stats = HashMap::from_iter(snapshot.snakes_stats);
+        world.snakes_lives = HashMap::from_iter(snapshot.snakes_lives);
+        world.snakes_respawns = snapshot.snakes_respawns;
+        world.match_end = snapshot.match_end;
+        world.eat_points = HashMap::from_iter(snapshot.eat_points);
+        world.set_eat_layers();
+        world.border_points = HashSet::from_iter(snapshot.border_points);
+        world
+            .world_mask
+            .set_layer(ObjectType::Border, world.border_points.clone());
+        Ok(world)
+    }
+    fn is_snapshot_consistent(snapshot: &WorldSnapshot) -> bool {
+        if snapshot.eat_points.len() > snapshot.config.rules.eat_count as usize {
+            return false;
+        }
+        let mut occupied_points: HashSet<Point<AreaSize>> =
+            HashSet::from_iter(snapshot.border_points.iter().copied());
+        for snake_snapshot in snapshot.snakes.values() {
+            let snake_points: HashSet<Point<AreaSize>> =
+                HashSet::from_iter(snake_snapshot.body_parts_points.iter().copied());
+            if !occupied_points.is_disjoint(&snake_points) {
+                return false;
+            }
+            occupied_points.extend(snake_points);
+        }
+        snapshot
+            .eat_points
+            .iter()
+            .all(|(point, _)| occupied_points.insert(*point))
+    }
+    pub(crate) fn set_replayed_timeouts(&mut self, timeouts: BTreeMap<usize, Vec<MovePhase>>) {
+        self.replayed_timeouts = timeouts;
+    }
+    pub fn tick(&mut self, reset: bool) -> WorldView<'_> {
+        self.events.clear();
+        self.replay.record_tick(reset);
+        if reset || self.start_time.is_none() {
+            self.start_time = Some(Instant::now());
+        }
         if reset {
             self.spawn_border();
             self.spawn_snakes()
         }
-        let points_move_vectors = self.snakes_move();
-        let SnakesInteractionsDetectResult {
-            snakes_to_remove,
-            snakes_that_ate_food,
-            snakes_that_bit_tail,
-        } = self.snakes_interactions_detect(&points_move_vectors);
-        self.handle_snakes_to_remove(snakes_to_remove);
-        self.handle_snakes_that_bit_tail(snakes_that_bit_tail);
-        self.handle_snakes_that_ate_food(snakes_that_ate_food);
+        self.respawn_snakes();
+        for phase in [MovePhase::Boost, MovePhase::Common] {
+            let has_moving_snakes = self
+                .snakes_info
+                .values()
+                .any(|snake_info| snake_info.should_move(phase, self.tick_number));
+            if !has_moving_snakes {
+                continue;
+            }
+            let SnakesMoveResult {
+                points_move_vectors,
+                previous_snakes,
+            } = self.snakes_move(phase);
+            let SnakesInteractionsDetectResult {
+                snakes_to_remove,
+                snakes_to_bounce,
+                snakes_that_ate_food,
+                snakes_that_bit_tail,
+            } = self.snakes_interactions_detect(&points_move_vectors, &previous_snakes);
+            self.handle_snakes_to_remove(snakes_to_remove);
+            self.handle_snakes_to_bounce(snakes_to_bounce, previous_snakes);
+            self.handle_snakes_that_bit_tail(snakes_that_bit_tail);
+            self.handle_snakes_that_ate_food(snakes_that_ate_food);
+        }
+        self.update_snakes_stats();
+        self.expire_speed_effects();
         self.spawn_eat();
+        self.tick_number += 1;
+        if self.match_end.is_none() {
+            self.match_end = self.match_end_detect();
+        }
         WorldView::new(self)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::super::fixtures::{self, greedy_controllers};
+    use super::super::replay::ReplaySnakeController;
+    use super::*;
+
+    use std::collections::VecDeque;
+
+    struct CountingRng {
+        state: u64,
+    }
+
+    impl Rng for CountingRng {
+        fn get_kind(&self) -> &str {
+            "counting"
+        }
+        fn next_u64(&mut self) -> u64 {
+            self.state = self.state.wrapping_add(7);
+            self.state
+        }
+        fn get_state(&self) -> u64 {
+            self.state
+        }
+        fn set_state(&mut self, state: u64) {
+            self.state = state;
+        }
+    }
+
+    fn test_config(seed: u64) -> Config {
+        let mut config = fixtures::config(20, seed);
+        config.rules.eat_count = 3;
+        config.rules.base_snake_tail_size = 3;
+        config.rules.lives = 3;
+        config.rules.respawn_delay = 2;
+        config.snakes_controllers = greedy_controllers(2);
+        config
+    }
+
+    fn world_state(world_view: &WorldView) -> Vec<(usize, Vec<Point<AreaSize>>, SnakeStats)> {
+        let mut snakes_numbers: Vec<usize> =
+            world_view.get_snakes_stats().keys().copied().collect();
+        snakes_numbers.sort();
+        snakes_numbers
+            .into_iter()
+            .map(|snake_number| {
+                let body_points = match world_view.get_snakes_info().get(&snake_number) {
+                    Some(snake_info) => snake_info.get_snake().body_parts_points(true),
+                    None => Vec::new(),
+                };
+                let snake_stats = world_view.get_snakes_stats()[&snake_number];
+                (snake_number, body_points, snake_stats)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn same_seed_gives_same_result() {
+        let run = |seed: u64| {
+            let mut world = World::new(test_config(seed)).unwrap();
+            world.tick(true);
+            for _ in 0..200 {
+                world.tick(false);
+            }
+            world.snapshot().to_json().unwrap()
+        };
+        assert_eq!(run(5), run(5));
+        assert_ne!(run(5), run(6));
+    }
+
+    #[test]
+    fn replay_round_trip_reproduces_world() {
+        let mut world = World::new(test_config(7)).unwrap();
+        let mut world_states = vec![world_state(&world.tick(true))];
+        for _ in 0..150 {
+            world_states.push(world_state(&world.tick(false)));
+        }
+        let replay = Replay::from_json(&world.get_replay().to_json().unwrap()).unwrap();
+        assert_eq!(replay.get_ticks().len(), world_states.len());
+        let mut replayed_states = Vec::new();
+        replay
+            .simulate(|world_view| replayed_states.push(world_state(world_view)))
+            .unwrap();
+        assert_eq!(replayed_states, world_states);
+    }
+
+    #[test]
+    fn restored_world_replay_continues_from_snapshot() {
+        let mut world = World::new(test_config(11)).unwrap();
+        world.tick(true);
+        for _ in 0..30 {
+            world.tick(false);
+        }
+        let snapshot = WorldSnapshot::from_json(&world.snapshot().to_json().unwrap()).unwrap();
+        let mut restored_world = World::from_snapshot(snapshot, greedy_controllers(2)).unwrap();
+        let mut restored_states = Vec::new();
+        for _ in 0..100 {
+            world.tick(false);
+            restored_states.push(world_state(&restored_world.tick(false)));
+        }
+        let world_json = world.snapshot().to_json().unwrap();
+        assert_eq!(world_json, restored_world.snapshot().to_json().unwrap());
+        let replay = restored_world.get_replay().clone();
+        assert!(replay.get_start_snapshot().is_some());
+        let mut replayed_states = Vec::new();
+        replay
+            .simulate(|world_view| replayed_states.push(world_state(world_view)))
+            .unwrap();
+        assert_eq!(replayed_states, restored_states);
+    }
+
+    #[test]
+    fn snapshot_keeps_rng_kind() {
+        let rng = Box::new(CountingRng { state: 5 });
+        let mut world = World::new_with_rng(test_config(3), rng).unwrap();
+        world.tick(true);
+        let snapshot = world.snapshot();
+        assert_eq!(snapshot.rng_kind, "counting");
+        assert!(matches!(
+            World::from_snapshot(snapshot.clone(), greedy_controllers(2)),
+            Err(CreateError::InvalidSnapshot)
+        ));
+        let rng = Box::new(CountingRng { state: 0 });
+        let restored_world =
+            World::from_snapshot_with_rng(snapshot.clone(), greedy_controllers(2), rng).unwrap();
+        assert_eq!(restored_world.snapshot().rng_state, snapshot.rng_state);
+    }
+
+    #[test]
+    fn inconsistent_snapshot_is_rejected() {
+        let mut world = World::new(test_config(4)).unwrap();
+        world.tick(true);
+        let snapshot = world.snapshot();
+        let food_kind = snapshot.eat_points[0].1;
+        let head_point = *snapshot.snakes[&0].body_parts_points.last().unwrap();
+        let border_point = snapshot.border_points[0];
+        let mut too_much_food = snapshot.clone();
+        too_much_food.config.rules.eat_count = 1;
+        let mut food_on_snake = snapshot.clone();
+        food_on_snake.eat_points[0] = (head_point, food_kind);
+        let mut snake_in_wall = snapshot.clone();
+        snake_in_wall
+            .snakes
+            .get_mut(&1)
+            .unwrap()
+            .body_parts_points
+            .push(border_point);
+        let mut food_in_wall = snapshot.clone();
+        food_in_wall.eat_points[0] = (border_point, food_kind);
+        for invalid_snapshot in [too_much_food, food_on_snake, snake_in_wall, food_in_wall] {
+            assert!(matches!(
+                World::from_snapshot(invalid_snapshot, greedy_controllers(2)),
+                Err(CreateError::InvalidSnapshot)
+            ));
+        }
+        assert!(World::from_snapshot(snapshot, greedy_controllers(2)).is_ok());
+    }
+
+    fn scripted_config(collision_rule: CollisionRule, spawns: Vec<Spawn>) -> Config {
+        let mut config = test_config(1);
+        config.rules.collision_rule = collision_rule;
+        config.rules.lives = 1;
+        config.rules.eat_count = 1;
+        config.food_spawners = vec![Point::new(18, 18)];
+        config.snakes_controllers = HashMap::new();
+        for snake_number in 0..spawns.len() {
+            let controller = Arc::new(Mutex::new(ReplaySnakeController::new(Default::default())));
+            config.snakes_controllers.insert(snake_number, controller);
+        }
+        config.rules.base_snake_tail_size = 2;
+        config.spawns = spawns;
+        config
+    }
+
+    fn script(config: &mut Config, snake_number: usize, directions: &[Direction]) {
+        let directions = VecDeque::from(directions.to_vec());
+        let controller = Arc::new(Mutex::new(ReplaySnakeController::new(directions)));
+        config.snakes_controllers.insert(snake_number, controller);
+    }
+
+    fn spawn(x: AreaSize, y: AreaSize, direction: Direction) -> Spawn {
+        Spawn {
+            point: Point::new(x, y),
+            direction,
+        }
+    }
+
+    #[test]
+    fn both_die_rule_spares_body_hit_victim() {
+        let config = scripted_config(
+            CollisionRule::BothDie,
+            vec![spawn(2, 5, Direction::Right), spawn(6, 3, Direction::Down)],
+        );
+        let mut world = World::new(config).unwrap();
+        world.tick(true);
+        let world_view = world.tick(false);
+        let death = Death {
+            cause: DeathCause::SnakeCollision,
+            killer: Some(1),
+        };
+        assert_eq!(world_view.get_snakes_stats()[&0].death, Some(death));
+        assert!(world_view.get_snakes_info().contains_key(&1));
+    }
+
+    #[test]
+    fn head_to_head_with_equal_lengths_kills_both() {
+        let config = scripted_config(
+            CollisionRule::LongerWins,
+            vec![spawn(2, 5, Direction::Right), spawn(10, 5, Direction::Left)],
+        );
+        let mut world = World::new(config).unwrap();
+        world.tick(true);
+        let world_view = world.tick(false);
+        for (snake_number, killer) in [(0, 1), (1, 0)] {
+            let death = Death {
+                cause: DeathCause::HeadToHead,
+                killer: Some(killer),
+            };
+            assert_eq!(
+                world_view.get_snakes_stats()[&snake_number].death,
+                Some(death)
+            );
+        }
+    }
+
+    #[test]
+    fn head_to_head_with_unequal_lengths_kills_shorter() {
+        let mut config = scripted_config(
+            CollisionRule::LongerWins,
+            vec![spawn(2, 5, Direction::Right), spawn(14, 5, Direction::Left)],
+        );
+        config.rules.food_weights = vec![(FoodKind::Normal, 1)];
+        config.food_spawners = vec![Point::new(6, 5)];
+        let mut world = World::new(config).unwrap();
+        world.tick(true);
+        world.tick(false);
+        world.tick(false);
+        let world_view = world.tick(false);
+        let death = Death {
+            cause: DeathCause::HeadToHead,
+            killer: Some(0),
+        };
+        assert_eq!(world_view.get_snakes_stats()[&1].death, Some(death));
+        assert_eq!(world_view.get_snakes_stats()[&0].death, None);
+        assert!(snake_body(&world).len() > 3);
+    }
+
+    #[test]
+    fn swapping_heads_is_a_head_to_head() {
+        let config = scripted_config(
+            CollisionRule::BothDie,
+            vec![spawn(2, 5, Direction::Right), spawn(9, 5, Direction::Left)],
+        );
+        let mut world = World::new(config).unwrap();
+        world.tick(true);
+        let world_view = world.tick(false);
+        for snake_number in [0, 1] {
+            let death = world_view.get_snakes_stats()[&snake_number].death;
+            assert_eq!(death.map(|death| death.cause), Some(DeathCause::HeadToHead));
+        }
+    }
+
+    #[test]
+    fn head_to_neck_is_a_body_hit() {
+        let config = scripted_config(
+            CollisionRule::BothDie,
+            vec![spawn(2, 5, Direction::Right), spawn(6, 2, Direction::Down)],
+        );
+        let mut world = World::new(config).unwrap();
+        world.tick(true);
+        let world_view = world.tick(false);
+        let death = Death {
+            cause: DeathCause::SnakeCollision,
+            killer: Some(1),
+        };
+        assert_eq!(world_view.get_snakes_stats()[&0].death, Some(death));
+        assert_eq!(world_view.get_snakes_stats()[&1].death, None);
+    }
+
+    #[test]
+    fn following_a_leaving_tail_is_free() {
+        let config = scripted_config(
+            CollisionRule::BothDie,
+            vec![spawn(2, 5, Direction::Right), spawn(6, 4, Direction::Down)],
+        );
+        let mut world = World::new(config).unwrap();
+        world.tick(true);
+        let world_view = world.tick(false);
+        assert_eq!(
+            world_view.get_snakes_info()[&0].get_snake().head_point(),
+            Point::new(6, 5)
+        );
+        assert_eq!(world_view.get_snakes_stats()[&0].death, None);
+        assert_eq!(world_view.get_snakes_stats()[&1].death, None);
+    }
+
+    #[test]
+    fn torus_collisions_are_detected_across_the_seam() {
+        let mut config = scripted_config(
+            CollisionRule::BothDie,
+            vec![spawn(15, 5, Direction::Right), spawn(3, 5, Direction::Left)],
+        );
+        config.rules.topology = Topology::Torus;
+        let mut world = World::new(config).unwrap();
+        world.tick(true);
+        let world_view = world.tick(false);
+        for snake_number in [0, 1] {
+            let death = world_view.get_snakes_stats()[&snake_number].death;
+            assert_eq!(death.map(|death| death.cause), Some(DeathCause::HeadToHead));
+        }
+    }
+
+    #[test]
+    fn bounce_rule_bounces_snakes_blocked_by_restored_body() {
+        let config = scripted_config(
+            CollisionRule::Bounce,
+            vec![
+                spawn(1, 5, Direction::Right),
+                spawn(9, 5, Direction::Left),
+                spawn(2, 1, Direction::Down),
+            ],
+        );
+        let mut world = World::new(config).unwrap();
+        world.tick(true);
+        let world_view = world.tick(false);
+        let bounced: Vec<usize> = world_view
+            .get_events()
+            .iter()
+            .filter_map(|event| match event {
+                Event::Bounced { snake } => Some(*snake),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(bounced, vec![0, 1, 2]);
+        let mut occupied_points = HashSet::new();
+        for snake_info in world_view.get_snakes_info().values() {
+            for point in snake_info.get_snake().body_parts_points(true) {
+                assert!(occupied_points.insert(point));
+            }
+        }
+    }
+
+    #[test]
+    fn biting_own_tail_is_not_a_tail_cut() {
+        let mut config = scripted_config(
+            CollisionRule::AttackerWins,
+            vec![spawn(8, 5, Direction::Right)],
+        );
+        config.rules.base_snake_tail_size = 4;
+        script(
+            &mut config,
+            0,
+            &[
+                Direction::Right,
+                Direction::Down,
+                Direction::Left,
+                Direction::Up,
+            ],
+        );
+        let mut world = World::new(config).unwrap();
+        let mut events = world.tick(true).get_events().clone();
+        for _ in 0..3 {
+            events.extend(world.tick(false).get_events().iter().copied());
+        }
+        assert!(events.iter().any(|event| matches!(
+            event,
+            Event::CutTail {
+                cutter: 0,
+                victim: 0,
+                ..
+            }
+        )));
+        let snake_stats = world.get_snakes_stats()[&0];
+        assert!(snake_stats.segments_lost > 0);
+        assert_eq!(snake_stats.tails_cut, 0);
+    }
+
+    fn food_config(food_kind: FoodKind, food_spawners: Vec<Point<AreaSize>>) -> Config {
+        let mut config = scripted_config(
+            CollisionRule::AttackerWins,
+            vec![spawn(2, 5, Direction::Right)],
+        );
+        config.rules.food_weights = vec![(food_kind, 1)];
+        config.rules.eat_count = food_spawners.len() as AreaSize;
+        config.food_spawners = food_spawners;
+        config
+    }
+
+    fn snake_body(world: &World) -> Vec<Point<AreaSize>> {
+        world.snakes_info[&0].snake.body_parts_points(true)
+    }
+
+    #[test]
+    fn big_food_grows_snake() {
+        let config = food_config(FoodKind::Big, vec![Point::new(6, 5)]);
+        let mut world = World::new(config).unwrap();
+        world.tick(true);
+        let world_view = world.tick(false);
+        assert!(world_view.get_events().contains(&Event::Ate {
+            snake: 0,
+            kind: FoodKind::Big,
+            point: Point::new(6, 5),
+        }));
+        for _ in 0..4 {
+            world.tick(false);
+        }
+        assert_eq!(snake_body(&world).len(), 3 + default_big_food_growth());
+    }
+
+    #[test]
+    fn poison_shrinks_snake_and_kills_it_at_minimum_length() {
+        let mut config = food_config(FoodKind::Poison, vec![Point::new(6, 5), Point::new(8, 5)]);
+        config.rules.poison_food_shrink = 2;
+        let mut world = World::new(config).unwrap();
+        world.tick(true);
+        world.tick(false);
+        assert_eq!(snake_body(&world), vec![Point::new(6, 5)]);
+        world.tick(false);
+        world.tick(false);
+        let death = Death {
+            cause: DeathCause::Poison,
+            killer: None,
+        };
+        assert_eq!(world.get_snakes_stats()[&0].death, Some(death));
+        assert!(!world.snakes_info.contains_key(&0));
+    }
+
+    #[test]
+    fn speed_boost_moves_twice_per_tick() {
+        let config = food_config(FoodKind::SpeedBoost, vec![Point::new(6, 5)]);
+        let mut world = World::new(config).unwrap();
+        world.tick(true);
+        world.tick(false);
+        assert_eq!(world.snakes_info[&0].snake.head_point(), Point::new(6, 5));
+        world.tick(false);
+        assert_eq!(world.snakes_info[&0].snake.head_point(), Point::new(8, 5));
+        world.tick(false);
+        assert_eq!(world.snakes_info[&0].snake.head_point(), Point::new(10, 5));
+    }
+
+    #[test]
+    fn slow_food_halves_speed() {
+        let config = food_config(FoodKind::Slow, vec![Point::new(6, 5)]);
+        let mut world = World::new(config).unwrap();
+        world.tick(true);
+        world.tick(false);
+        assert_eq!(world.snakes_info[&0].snake.head_point(), Point::new(6, 5));
+        for _ in 0..4 {
+            world.tick(false);
+        }
+        assert_eq!(world.snakes_info[&0].snake.head_point(), Point::new(8, 5));
+    }
+
+    #[test]
+    fn food_kinds_follow_spawn_weights() {
+        let mut config = test_config(9);
+        config.rules.food_weights = vec![
+            (FoodKind::Normal, 3),
+            (FoodKind::Big, 1),
+            (FoodKind::Poison, 0),
+        ];
+        let mut world = World::new(config).unwrap();
+        let mut counts = HashMap::new();
+        for _ in 0..4000 {
+            *counts.entry(world.random_food_kind()).or_insert(0) += 1;
+        }
+        assert!((2800..3200).contains(&counts[&FoodKind::Normal]));
+        assert!((800..1200).contains(&counts[&FoodKind::Big]));
+        assert!(!counts.contains_key(&FoodKind::Poison));
+    }
+
+    #[test]
+    fn last_snake_standing_without_survivors_is_a_draw() {
+        let mut config = scripted_config(
+            CollisionRule::AttackerWins,
+            vec![spawn(1, 5, Direction::Right), spawn(9, 5, Direction::Left)],
+        );
+        config.rules.win_conditions = vec![WinCondition::LastSnakeStanding];
+        let mut world = World::new(config).unwrap();
+        world.tick(true);
+        world.tick(false);
+        let match_result = world.match_result();
+        assert_eq!(match_result.end, Some(MatchEnd::NoSnakesLeft));
+        assert!(match_result.winners.is_empty());
+    }
+
+    #[test]
+    fn kills_winners_are_snakes_with_enough_kills() {
+        let mut config = scripted_config(
+            CollisionRule::BothDie,
+            vec![
+                spawn(1, 5, Direction::Right),
+                spawn(9, 5, Direction::Left),
+                spawn(2, 12, Direction::Right),
+            ],
+        );
+        config.rules.win_conditions = vec![WinCondition::Kills(1)];
+        let mut world = World::new(config).unwrap();
+        world.tick(true);
+        world.tick(false);
+        let match_result = world.match_result();
+        assert_eq!(
+            match_result.end,
+            Some(MatchEnd::Win(WinCondition::Kills(1)))
+        );
+        assert_eq!(world.get_snakes_stats()[&0].kills, 1);
+        assert_eq!(world.get_snakes_stats()[&1].kills, 1);
+        assert_eq!(world.get_snakes_stats()[&2].death, None);
+        assert_eq!(match_result.winners, vec![0, 1]);
+        assert_eq!(match_result.ranking[2], 2);
+    }
+
+    #[test]
+    fn time_limit_ends_match() {
+        let mut config = test_config(5);
+        config.rules.win_conditions = vec![WinCondition::TimeLimit(Duration::ZERO)];
+        let mut world = World::new(config).unwrap();
+        world.tick(true);
+        let end = Some(MatchEnd::Win(WinCondition::TimeLimit(Duration::ZERO)));
+        assert_eq!(*world.get_match_end(), end);
+    }
+
+    #[test]
+    fn respawn_clears_death() {
+        let mut config = scripted_config(
+            CollisionRule::AttackerWins,
+            vec![
+                spawn(14, 5, Direction::Right),
+                spawn(3, 12, Direction::Right),
+            ],
+        );
+        config.rules.lives = 2;
+        config.rules.respawn_delay = 0;
+        let mut world = World::new(config).unwrap();
+        world.tick(true);
+        for _ in 0..2 {
+            world.tick(false);
+        }
+        let snake_stats = world.get_snakes_stats()[&0];
+        assert_eq!(
+            snake_stats.death.map(|death| death.cause),
+            Some(DeathCause::Wall)
+        );
+        world.tick(false);
+        let snake_stats = world.get_snakes_stats()[&0];
+        assert_eq!(snake_stats.deaths, 1);
+        assert_eq!(snake_stats.death, None);
+    }
+
+    struct BlockedSnakeController {
+        started: mpsc::Sender<()>,
+        release: mpsc::Receiver<()>,
+    }
+
+    impl SnakeController for BlockedSnakeController {
+        fn snake_will_burn(&mut self, _: &WorldView) {}
+        fn snake_did_burn(&mut self, _: &SnakeInfo, _: &WorldView) {}
+        fn snake_will_move(&mut self, _: &SnakeInfo, _: &WorldView) -> Direction {
+            let _ = self.started.send(());
+            let _ = self.release.recv();
+            Direction::Down
+        }
+        fn snake_did_move(&mut self, _: &SnakeInfo, _: &WorldView) {}
+        fn snake_will_eat(&mut self, _: EatType, _: &SnakeInfo, _: &WorldView) {}
+        fn snake_did_eat(&mut self, _: EatType, _: &SnakeInfo, _: &WorldView) {}
+        fn snake_will_died(&mut self, _: &SnakeInfo, _: &WorldView) {}
+        fn snake_did_died(&mut self, _: &WorldView) {}
+    }
+
+    #[test]
+    fn move_deadline_does_not_wait_for_slow_controller() {
+        let mut config = scripted_config(
+            CollisionRule::AttackerWins,
+            vec![spawn(2, 5, Direction::Right)],
+        );
+        let (started_sender, started) = mpsc::channel();
+        let (release, release_receiver) = mpsc::channel();
+        let controller = Arc::new(Mutex::new(BlockedSnakeController {
+            started: started_sender,
+            release: release_receiver,
+        }));
+        config.snakes_controllers.insert(0, controller.clone());
+        config.rules.move_deadline = Some(Duration::from_millis(200));
+        let mut world = World::new(config).unwrap();
+        world.tick(true);
+        started.recv().unwrap();
+        for _ in 0..3 {
+            world.tick(false);
+        }
+        let world_view = world.tick(false);
+        assert_eq!(world_view.get_snakes_stats()[&0].timeouts, 5);
+        let head_point = world_view.get_snakes_info()[&0].get_snake().head_point();
+        assert_eq!(head_point, Point::new(9, 5));
+        release.send(()).unwrap();
+        drop(controller.lock().unwrap());
+        release.send(()).unwrap();
+        let world_view = world.tick(false);
+        assert_eq!(world_view.get_snakes_stats()[&0].timeouts, 5);
+        let head_point = world_view.get_snakes_info()[&0].get_snake().head_point();
+        assert_eq!(head_point, Point::new(9, 6));
+        assert_eq!(started.try_iter().count(), 1);
+    }
+
+    #[test]
+    fn replay_keeps_timed_out_moves() {
+        let mut config = scripted_config(
+            CollisionRule::AttackerWins,
+            vec![spawn(2, 5, Direction::Right)],
+        );
+        let (started, _) = mpsc::channel();
+        let (_release, release) = mpsc::channel();
+        let controller = BlockedSnakeController { started, release };
+        config
+            .snakes_controllers
+            .insert(0, Arc::new(Mutex::new(controller)));
+        config.rules.move_deadline = Some(Duration::from_millis(10));
+        let mut world = World::new(config).unwrap();
+        let mut world_states = Vec::new();
+        for tick_number in 0..4 {
+            let world_view = world.tick(tick_number == 0);
+            world_states.push((world_state(&world_view), world_view.get_events().clone()));
+        }
+        let replay = world.get_replay().clone();
+        assert_eq!(replay.get_ticks()[1].timeouts[&0], vec![MovePhase::Common]);
+        let mut replayed_states = Vec::new();
+        replay
+            .simulate(|world_view| {
+                replayed_states.push((world_state(world_view), world_view.get_events().clone()))
+            })
+            .unwrap();
+        assert_eq!(replayed_states, world_states);
+    }
+
+    #[derive(Default)]
+    struct ObservingSnakeController {
+        visible_stats: Vec<Vec<usize>>,
+    }
+
+    impl SnakeController for ObservingSnakeController {
+        fn snake_will_burn(&mut self, _: &WorldView) {}
+        fn snake_did_burn(&mut self, _: &SnakeInfo, _: &WorldView) {}
+        fn snake_will_move(&mut self, self_info: &SnakeInfo, world_view: &WorldView) -> Direction {
+            let mut snakes_numbers: Vec<usize> =
+                world_view.get_snakes_stats().keys().copied().collect();
+            snakes_numbers.sort();
+            self.visible_stats.push(snakes_numbers);
+            self_info.get_direction().unwrap_or(Direction::Right)
+        }
+        fn snake_did_move(&mut self, _: &SnakeInfo, _: &WorldView) {}
+        fn snake_will_eat(&mut self, _: EatType, _: &SnakeInfo, _: &WorldView) {}
+        fn snake_did_eat(&mut self, _: EatType, _: &SnakeInfo, _: &WorldView) {}
+        fn snake_will_died(&mut self, _: &SnakeInfo, _: &WorldView) {}
+        fn snake_did_died(&mut self, _: &WorldView) {}
+    }
+
+    #[test]
+    fn vision_hides_other_snakes_stats() {
+        let mut config = scripted_config(
+            CollisionRule::AttackerWins,
+            vec![
+                spawn(2, 3, Direction::Right),
+                spawn(2, 12, Direction::Right),
+            ],
+        );
+        let controller = Arc::new(Mutex::new(ObservingSnakeController::default()));
+        config.snakes_controllers.insert(0, controller.clone());
+        config.rules.vision_radius = Some(3);
+        let mut world = World::new(config).unwrap();
+        world.tick(true);
+        world.tick(false);
+        let controller = controller.lock().unwrap();
+        assert_eq!(controller.visible_stats.last(), Some(&vec![0]));
+        assert_eq!(world.get_snakes_stats().len(), 2);
+    }
+}
@@ -1,41 +1,71 @@
 use super::snake::game::{
-    ActionType as GameActionType, Config as GameConfig, GameController, TickType as GameTickType,
+    ActionType as GameActionType, Config as GameConfig, GameController, GameResult,
+    TickType as GameTickType,
 };
+use super::snake::level::Level;
 use super::snake::world::{
-    Config as WorldConfig, CreateError as WorldCreateError, ObjectType as WorldObjectType,
-    SnakeController, SnakeInfo, WorldView,
+    default_food_weights, default_lives, CollisionRule as WorldCollisionRule,
+    Config as WorldConfig, CreateError as WorldCreateError, EatType, FoodKind,
+    ObjectType as WorldObjectType, Rules as WorldRules,
+    SelfCollisionRule as WorldSelfCollisionRule, SnakeController, SnakeInfo,
+    Topology as WorldTopology, WinCondition, WorldView,
 };
-use super::snake::{Direction, Point};
+use super::snake::{current_time_seed, Direction, Point};
 use super::terminal::{KeyCode, Terminal, TerminalSize};
 
-use std::cell::RefCell;
 use std::collections::HashMap;
-use std::rc::Rc;
+use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::{Duration, SystemTime};
 
-pub fn new() -> GameConfig {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rules {
+    pub lives: usize,
+    pub respawn_delay: usize,
+    pub food_kinds: bool,
+    pub last_snake_standing: bool,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self {
+            lives: default_lives(),
+            respawn_delay: 0,
+            food_kinds: false,
+            last_snake_standing: false,
+        }
+    }
+}
+
+pub fn new(replay_path: Option<String>, level: Option<Level>, rules: Rules) -> GameConfig {
+    let game_controller = TerminalGameController::new(replay_path, level, rules);
     GameConfig {
-        game_controller: Rc::new(RefCell::new(TerminalGameController::new())),
+        game_controller: Arc::new(Mutex::new(game_controller)),
     }
 }
 
 struct TerminalGameController {
     terminal: Terminal,
+    replay_path: Option<String>,
+    level: Option<Level>,
+    rules: Rules,
     last_tick_start: Option<SystemTime>,
-    first_snake: Rc<RefCell<DirectionSnakeController>>,
-    second_snake: Rc<RefCell<DirectionSnakeController>>,
+    first_snake: Arc<Mutex<DirectionSnakeController>>,
+    second_snake: Arc<Mutex<DirectionSnakeController>>,
 }
 
 impl TerminalGameController {
-    fn new() -> Self {
+    fn new(replay_path: Option<String>, level: Option<Level>, rules: Rules) -> Self {
         Self {
             terminal: Terminal::new(),
+            replay_path,
+            level,
+            rules,
             last_tick_start: None,
-            first_snake: Rc::new(RefCell::new(DirectionSnakeController {
+            first_snake: Arc::new(Mutex::new(DirectionSnakeController {
                 next_direction: Direction::Right,
             })),
-            second_snake: Rc::new(RefCell::new(DirectionSnakeController {
+            second_snake: Arc::new(Mutex::new(DirectionSnakeController {
                 next_direction: Direction::Right,
             })),
         }
@@ -58,10 +88,10 @@ impl TerminalGameController {
 
 impl GameController for TerminalGameController {
     fn game_action(&mut self) -> GameActionType {
-        if let Ok(mut first_snake) = self.first_snake.try_borrow_mut() {
+        if let Ok(mut first_snake) = self.first_snake.lock() {
             first_snake.next_direction = Direction::Right;
         }
-        if let Ok(mut second_snake) = self.second_snake.try_borrow_mut() {
+        if let Ok(mut second_snake) = self.second_snake.lock() {
             second_snake.next_direction = Direction::Right;
         }
         let last_tick_start = self.last_tick_start;
@@ -74,22 +104,58 @@ impl GameController for TerminalGameController {
     fn game_start(&mut self) -> WorldConfig {
         let _ = Terminal::enable_raw_mode();
         let _ = self.terminal.clear();
-        let mut controllers = HashMap::<usize, Rc<RefCell<dyn SnakeController>>>::new();
+        let mut controllers = HashMap::<usize, Arc<Mutex<dyn SnakeController>>>::new();
         controllers.insert(0, self.first_snake.clone());
         controllers.insert(1, self.second_snake.clone());
-        WorldConfig {
-            world_size: Terminal::size().unwrap_or((50, 50)),
-            eat_count: 3,
-            cut_tails: true,
-            base_snake_tail_size: 3,
+        let world_config = WorldConfig {
+            rules: WorldRules {
+                world_size: Terminal::size().unwrap_or((50, 50)),
+                eat_count: 3,
+                collision_rule: WorldCollisionRule::AttackerWins,
+                self_collision_rule: WorldSelfCollisionRule::CutTail,
+                base_snake_tail_size: 3,
+                topology: WorldTopology::Bounded,
+                food_weights: if self.rules.food_kinds {
+                    vec![
+                        (FoodKind::Normal, 12),
+                        (FoodKind::Big, 2),
+                        (FoodKind::Poison, 2),
+                        (FoodKind::SpeedBoost, 1),
+                        (FoodKind::Slow, 1),
+                    ]
+                } else {
+                    default_food_weights()
+                },
+                big_food_growth: 3,
+                poison_food_shrink: 2,
+                speed_effect_ticks: 20,
+                lives: self.rules.lives,
+                respawn_delay: self.rules.respawn_delay,
+                win_conditions: if self.rules.last_snake_standing {
+                    vec![WinCondition::LastSnakeStanding]
+                } else {
+                    Vec::new()
+                },
+                move_deadline: None,
+                vision_radius: None,
+            },
+            seed: current_time_seed().unwrap_or_default(),
+            walls: Vec::new(),
+            spawns: Vec::new(),
+            food_spawners: Vec::new(),
+            record_replay: true,
             snakes_controllers: controllers,
+        };
+        match &self.level {
+            Some(level) => level.world_config(world_config),
+            None => world_config,
         }
     }
     fn game_will_tick(&mut self, previous_world_view: &Option<WorldView>) -> GameTickType {
         self.delay_if_needed();
         match previous_world_view {
             Some(world_view) => {
-                if world_view.get_snakes_info().len() == 0 {
+                if world_view.get_match_end().is_some() {
                     return GameTickType::Break;
                 }
                 let current_key_code = Terminal::current_key_code(Duration::from_millis(0));
@@ -98,7 +164,7 @@ impl GameController for TerminalGameController {
                         if key_code == KeyCode::Esc {
                             return GameTickType::Break;
                         }
-                        match self.first_snake.try_borrow_mut() {
+                        match self.first_snake.lock() {
                             Ok(mut first_snake) => {
                                 first_snake.next_direction = match key_code {
                                     KeyCode::Char('d') => Direction::Right,
@@ -110,7 +176,7 @@ impl GameController for TerminalGameController {
                             }
                             Err(_) => return GameTickType::Break,
                         }
-                        match self.second_snake.try_borrow_mut() {
+                        match self.second_snake.lock() {
                             Ok(mut second_snake) => {
                                 second_snake.next_direction = match key_code {
                                     KeyCode::Right => Direction::Right,
@@ -139,16 +205,25 @@ impl GameController for TerminalGameController {
                 1 => 'x',
                 _ => unreachable!(),
             },
-            WorldObjectType::Eat => '@',
+            WorldObjectType::Eat(food_kind) => match food_kind {
+                FoodKind::Normal => '@',
+                FoodKind::Big => '$',
+                FoodKind::Poison => '%',
+                FoodKind::SpeedBoost => '+',
+                FoodKind::Slow => '-',
+            },
         };
         let map = world_view
             .get_world_mask()
             .generate_map(points_mapper, objects_mapper);
         let _ = self.terminal.render(&map);
     }
-    fn game_end(&mut self, _: Result<(), WorldCreateError>) {
+    fn game_end(&mut self, state: Result<GameResult, WorldCreateError>) {
         let _ = self.terminal.clear();
         let _ = Terminal::disable_raw_mode();
+        if let (Ok(game_result), Some(replay_path)) = (&state, &self.replay_path) {
+            let _ = game_result.replay.save(replay_path);
+        }
     }
 }
 
@@ -158,13 +233,17 @@ struct DirectionSnakeController {
 
 impl SnakeController for DirectionSnakeController {
     fn snake_will_burn(&mut self, _: &WorldView) {}
-    fn snake_did_burn(&mut self, _: &SnakeInfo, _: &WorldView) {}
+    fn snake_did_burn(&mut self, self_info: &SnakeInfo, _: &WorldView) {
+        if let Some(direction) = self_info.get_direction() {
+            self.next_direction = *direction;
+        }
+    }
     fn snake_will_move(&mut self, _: &SnakeInfo, _: &WorldView) -> Direction {
         self.next_direction
     }
     fn snake_did_move(&mut self, _: &SnakeInfo, _: &WorldView) {}
-    fn snake_will_eat(&mut self, _: bool, _: &SnakeInfo, _: &WorldView) {}
-    fn snake_did_eat(&mut self, _: bool, _: &SnakeInfo, _: &WorldView) {}
+    fn snake_will_eat(&mut self, _: EatType, _: &SnakeInfo, _: &WorldView) {}
+    fn snake_did_eat(&mut self, _: EatType, _: &SnakeInfo, _: &WorldView) {}
     fn snake_will_died(&mut self, _: &SnakeInfo, _: &WorldView) {}
     fn snake_did_died(&mut self, _: &WorldView) {}
 }
@@ -3,9 +3,50 @@ extern crate terminal;
 
 mod game_config;
 
-fn main() {
-    match snake::game::Game::new(game_config::new()) {
-        Ok(mut game) => game.start(),
-        Err(err) => println!("{:?}", err),
+use snake::level::Level;
+
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let mut replay_path = None;
+    let mut level_path = None;
+    let mut rules = game_config::Rules::default();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--replay" => replay_path = args.next(),
+            "--level" => level_path = args.next(),
+            "--lives" => {
+                if let Some(lives) = args.next().and_then(|value| value.parse().ok()) {
+                    rules.lives = lives;
+                }
+            }
+            "--respawn-delay" => {
+                if let Some(respawn_delay) = args.next().and_then(|value| value.parse().ok()) {
+                    rules.respawn_delay = respawn_delay;
+                }
+            }
+            "--food-kinds" => rules.food_kinds = true,
+            "--last-snake-standing" => rules.last_snake_standing = true,
+            _ => {}
+        }
+    }
+    let level = match level_path.map(Level::load) {
+        Some(Ok(level)) => Some(level),
+        Some(Err(err)) => {
+            eprintln!("{:?}", err);
+            return ExitCode::FAILURE;
+        }
+        None => None,
+    };
+    match snake::game::Game::new(game_config::new(replay_path, level, rules)) {
+        Ok(mut game) => {
+            game.start();
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("{:?}", err);
+            ExitCode::FAILURE
+        }
     }
 }
@@ -54,24 +54,24 @@ impl Terminal {
         for (point, pixel) in points_map {
             let is_space = *pixel == SPACE_CHAR;
             if !is_space {
-                self.cache.insert(point.clone(), pixel.clone());
+                self.cache.insert(*point, *pixel);
             }
-            if let Some(previous_pixel) = previous_cache.get(&point) {
+            if let Some(previous_pixel) = previous_cache.get(point) {
                 let should_skip_render = previous_pixel == pixel;
                 if !is_space {
-                    previous_cache.remove(&point);
+                    previous_cache.remove(point);
                 }
                 if should_skip_render {
                     continue;
                 }
             }
             self.stdout
-                .queue(cursor_move_to_command(point.clone()))?
-                .queue(print_styled_content_command(pixel.clone()))?;
+                .queue(cursor_move_to_command(*point))?
+                .queue(print_styled_content_command(*pixel))?;
         }
         for (point, _) in previous_cache {
             self.stdout
-                .queue(cursor_move_to_command(point.clone()))?
+                .queue(cursor_move_to_command(point))?
                 .queue(print_styled_content_command(SPACE_CHAR))?;
         }
         self.stdout.queue(cursor_move_to_command((0, 0)))?;
@@ -80,6 +80,12 @@ impl Terminal {
     }
 }
 
+impl Default for Terminal {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 fn cursor_move_to_command(point: TerminalPoint) -> cursor::MoveTo {
     cursor::MoveTo(point.0, point.1)
 }